@@ -0,0 +1,197 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{math, Ray, AABB};
+
+/// Casts one ray against boxes given as `min_x, min_y, min_z, max_x, max_y,
+/// max_z` per box, returning the `distance_to_aabb` of each box or `-1.0` for
+/// a miss.
+#[wasm_bindgen]
+pub fn raycast_batch(origin: &[f32], dir: &[f32], boxes_flat: &[f32]) -> Vec<f32> {
+    let ray = Ray::new(origin[0], origin[1], origin[2], dir[0], dir[1], dir[2]);
+
+    boxes_flat
+        .chunks_exact(6)
+        .map(|b| {
+            let aabb = AABB::new(b[0], b[1], b[2], b[3], b[4], b[5]);
+            ray.distance_to_aabb(&aabb).unwrap_or(-1.0)
+        })
+        .collect()
+}
+
+/// Index of the box nearest along the ray, in the same layout as
+/// `raycast_batch`, or `-1` if none is hit within `max_distance`. Boxes the
+/// origin is inside count as hit at distance 0; ties go to the lowest index.
+#[wasm_bindgen]
+pub fn raycast_nearest(origin: &[f32], dir: &[f32], boxes_flat: &[f32], max_distance: f32) -> i32 {
+    let ray = Ray::new(origin[0], origin[1], origin[2], dir[0], dir[1], dir[2]);
+    let mut nearest = None;
+    let mut limit = max_distance;
+
+    for (i, b) in boxes_flat.chunks_exact(6).enumerate() {
+        if let Some(t) = entry_within(&ray, b, limit) {
+            if nearest.is_none() || t < limit {
+                nearest = Some(i as i32);
+                limit = t;
+            }
+        }
+    }
+
+    nearest.unwrap_or(-1)
+}
+
+// Slab test that gives up as soon as the entry distance is known to exceed
+// `limit`, so boxes behind the current nearest hit are usually rejected after
+// one or two axes.
+fn entry_within(ray: &Ray, b: &[f32], limit: f32) -> Option<f32> {
+    let mut tmin = 0.0f32;
+    let mut tmax = f32::INFINITY;
+
+    for axis in 0..3 {
+        let (origin, direction) = (ray.origin[axis], ray.direction[axis]);
+        let (min, max) = (b[axis], b[axis + 3]);
+        if direction == 0.0 {
+            if origin < min || origin > max {
+                return None;
+            }
+            continue;
+        }
+
+        let t1 = (min - origin) / direction;
+        let t2 = (max - origin) / direction;
+        tmin = tmin.max(t1.min(t2));
+        tmax = tmax.min(t1.max(t2));
+        if tmin > tmax || tmin > limit {
+            return None;
+        }
+    }
+
+    Some(tmin)
+}
+
+/// Casts `n` rays against one box, returning the `distance_to_aabb` of each
+/// ray or `-1.0` for a miss. Origins and directions are laid out as
+/// structure-of-arrays, all x components, then all y, then all z:
+///
+/// ```text
+/// origins = [x0, x1, .., xn-1, y0, y1, .., yn-1, z0, z1, .., zn-1]
+/// ```
+///
+/// Each slab is processed across the whole packet in a branch-free loop, so
+/// the compiler can vectorize it. Returns `None` unless `origins` and `dirs`
+/// have the same length, a multiple of 3.
+#[wasm_bindgen]
+pub fn raycast_packet(origins: &[f32], dirs: &[f32], aabb: &AABB) -> Option<Vec<f32>> {
+    if origins.len() != dirs.len() || !origins.len().is_multiple_of(3) {
+        return None;
+    }
+    let n = origins.len() / 3;
+
+    // Normalize up front the same way `Ray::new` does, so results match the
+    // scalar path exactly.
+    let mut unit = vec![0.0; 3 * n];
+    for i in 0..n {
+        let d = math::vector(dirs[i], dirs[n + i], dirs[2 * n + i]).normalize();
+        (unit[i], unit[n + i], unit[2 * n + i]) = (d.x, d.y, d.z);
+    }
+
+    let mut tmin = vec![f32::NEG_INFINITY; n];
+    let mut tmax = vec![f32::INFINITY; n];
+    for axis in 0..3 {
+        let (lo, hi) = (aabb.min[axis], aabb.max[axis]);
+        let origin = &origins[axis * n..(axis + 1) * n];
+        let direction = &unit[axis * n..(axis + 1) * n];
+
+        for i in 0..n {
+            let (o, d) = (origin[i], direction[i]);
+            let inv = 1.0 / d;
+            let t1 = (lo - o) * inv;
+            let t2 = (hi - o) * inv;
+            // A ray parallel to the slab either spans it entirely or misses.
+            let inside = o >= lo && o <= hi;
+            let parallel_near = if inside { f32::NEG_INFINITY } else { f32::INFINITY };
+            let near = if d == 0.0 { parallel_near } else { t1.min(t2) };
+            let far = if d == 0.0 { -parallel_near } else { t1.max(t2) };
+            tmin[i] = tmin[i].max(near);
+            tmax[i] = tmax[i].min(far);
+        }
+    }
+
+    let distances = tmin
+        .iter()
+        .zip(&tmax)
+        .map(|(&enter, &exit)| if exit >= enter && exit >= 0.0 { enter.max(0.0) } else { -1.0 })
+        .collect();
+    Some(distances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::lcg_unit;
+
+    #[test]
+    fn raycast_nearest_picks_the_front_most_overlapping_box() {
+        let origin = [0.0, 0.5, 0.5];
+        let dir = [1.0, 0.0, 0.0];
+        let boxes = [
+            [5.0, 0.0, 0.0, 9.0, 1.0, 1.0],
+            [3.0, 0.0, 0.0, 6.0, 1.0, 1.0],
+            [4.0, 0.0, 0.0, 5.0, 1.0, 1.0],
+            [1.0, 5.0, 0.0, 2.0, 6.0, 1.0],
+        ]
+        .concat();
+        assert_eq!(raycast_nearest(&origin, &dir, &boxes, 100.0), 1);
+        assert_eq!(raycast_nearest(&origin, &dir, &boxes, 3.0), 1);
+        assert_eq!(raycast_nearest(&origin, &dir, &boxes, 2.9), -1);
+        assert_eq!(raycast_nearest(&origin, &dir, &boxes[..6], 100.0), 0);
+        assert_eq!(raycast_nearest(&origin, &dir, &[], 100.0), -1);
+    }
+
+    #[test]
+    fn raycast_nearest_counts_an_enclosing_box_at_zero_and_ties_go_low() {
+        let origin = [0.0, 0.5, 0.5];
+        let dir = [1.0, 0.0, 0.0];
+        let boxes = [2.0, 0.0, 0.0, 3.0, 1.0, 1.0, -1.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+        assert_eq!(raycast_nearest(&origin, &dir, &boxes, 0.0), 1);
+        let twins = [2.0, 0.0, 0.0, 3.0, 1.0, 1.0, 2.0, 0.0, 0.0, 4.0, 1.0, 1.0];
+        assert_eq!(raycast_nearest(&origin, &dir, &twins, 100.0), 0);
+        assert_eq!(raycast_batch(&origin, &dir, &twins), vec![2.0, 2.0]);
+    }
+
+    #[test]
+    fn raycast_packet_matches_the_scalar_path() {
+        let mut next = lcg_unit(12345);
+        let mut rng = move || next() * 8.0 - 4.0;
+
+        let aabb = AABB::new(-1.0, -0.5, -2.0, 1.5, 1.0, 0.5);
+        let n = 500;
+        let (mut origins, mut dirs) = (vec![0.0; 3 * n], vec![0.0; 3 * n]);
+        for i in 0..n {
+            for axis in 0..3 {
+                origins[axis * n + i] = rng();
+                // Roughly towards the box, with every fifth ray parallel to a slab.
+                let towards = rng() * 0.5 - origins[axis * n + i];
+                dirs[axis * n + i] = if i % 5 == 0 && axis == i % 3 { 0.0 } else { towards };
+            }
+        }
+
+        let packet = raycast_packet(&origins, &dirs, &aabb).unwrap();
+        let mut hits = 0;
+        for i in 0..n {
+            let o = [origins[i], origins[n + i], origins[2 * n + i]];
+            let d = [dirs[i], dirs[n + i], dirs[2 * n + i]];
+            let scalar = raycast_batch(&o, &d, &[-1.0, -0.5, -2.0, 1.5, 1.0, 0.5]);
+            assert_eq!(packet[i], scalar[0], "ray {i}");
+            hits += (packet[i] >= 0.0) as usize;
+        }
+        assert!(hits > 50 && hits < n - 50, "{hits}");
+    }
+
+    #[test]
+    fn raycast_packet_rejects_mismatched_layouts() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        assert!(raycast_packet(&[0.0; 6], &[1.0; 3], &aabb).is_none());
+        assert!(raycast_packet(&[0.0; 4], &[1.0; 4], &aabb).is_none());
+        assert_eq!(raycast_packet(&[], &[], &aabb), Some(vec![]));
+    }
+}
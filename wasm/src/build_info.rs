@@ -0,0 +1,64 @@
+use js_sys::{Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+/// Crate version and the optional Cargo features this build was compiled
+/// with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub serde: bool,
+    pub rayon: bool,
+    pub glam: bool,
+}
+
+impl BuildInfo {
+    pub const fn current() -> BuildInfo {
+        BuildInfo {
+            version: env!("CARGO_PKG_VERSION"),
+            serde: cfg!(feature = "serde"),
+            rayon: cfg!(feature = "rayon"),
+            glam: cfg!(feature = "glam"),
+        }
+    }
+}
+
+/// [`BuildInfo::current`] as a plain JS object:
+///
+/// ```js
+/// { version: "0.1.0", features: { serde: false, rayon: false, glam: true } }
+/// ```
+///
+/// `rayon` only reports the feature flag; the parallel helpers it enables are
+/// never available in wasm builds.
+#[wasm_bindgen]
+pub fn build_info() -> JsValue {
+    let info = BuildInfo::current();
+    let set = |object: &Object, key: &str, value: JsValue| {
+        Reflect::set(object, &JsValue::from_str(key), &value).expect("setting a property on a plain object");
+    };
+
+    let features = Object::new();
+    set(&features, "serde", info.serde.into());
+    set(&features, "rayon", info.rayon.into());
+    set(&features, "glam", info.glam.into());
+
+    let object = Object::new();
+    set(&object, "version", info.version.into());
+    set(&object, "features", features.into());
+    object.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn current_reports_the_compiled_features() {
+        let info = BuildInfo::current();
+        assert!(!info.version.is_empty());
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info.serde, cfg!(feature = "serde"));
+        assert_eq!(info.rayon, cfg!(feature = "rayon"));
+        assert_eq!(info.glam, cfg!(feature = "glam"));
+    }
+}
@@ -0,0 +1,185 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{math, Ray, AABB};
+
+const MAX_LEAF_SIZE: usize = 4;
+
+/// Bounding volume hierarchy over a fixed set of boxes, built by median split
+/// along the longest axis of the box centroids. Leaf ids are box indices.
+#[wasm_bindgen]
+pub struct Bvh {
+    boxes: Vec<AABB>,
+    order: Vec<u32>,
+    nodes: Vec<BvhNode>,
+}
+
+enum BvhNode {
+    Leaf { bounds: AABB, first: usize, count: usize },
+    Branch { bounds: AABB, left: usize, right: usize },
+}
+
+impl BvhNode {
+    fn bounds(&self) -> &AABB {
+        match self {
+            BvhNode::Leaf { bounds, .. } | BvhNode::Branch { bounds, .. } => bounds,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Bvh {
+    /// Builds from boxes given as `min_x, min_y, min_z, max_x, max_y, max_z`
+    /// per box.
+    pub fn build(boxes_flat: &[f32]) -> Bvh {
+        let boxes: Vec<AABB> = boxes_flat
+            .chunks_exact(6)
+            .map(|b| AABB::new(b[0], b[1], b[2], b[3], b[4], b[5]))
+            .collect();
+
+        let mut bvh = Bvh {
+            order: (0..boxes.len() as u32).collect(),
+            boxes,
+            nodes: Vec::new(),
+        };
+        if !bvh.boxes.is_empty() {
+            bvh.build_node(0, bvh.boxes.len());
+        }
+        bvh
+    }
+
+    /// JS form of [`Bvh::raycast`], returning `[id, t]`.
+    #[wasm_bindgen(js_name = raycast)]
+    pub fn raycast_js(&self, ray: &Ray) -> Option<Vec<f32>> {
+        self.raycast(ray).map(|(id, t)| vec![id as f32, t])
+    }
+}
+
+impl Bvh {
+    /// Nearest box hit by `ray` and its `distance_to_aabb`. Nodes are visited
+    /// front to back and skipped once they start beyond the best hit so far.
+    pub fn raycast(&self, ray: &Ray) -> Option<(u32, f32)> {
+        let mut best: Option<(u32, f32)> = None;
+        let mut stack = Vec::new();
+
+        if let Some(t) = self.nodes.first().and_then(|root| ray.distance_to_aabb(root.bounds())) {
+            stack.push((0, t));
+        }
+
+        while let Some((index, entry)) = stack.pop() {
+            if best.is_some_and(|(_, t)| entry > t) {
+                continue;
+            }
+
+            match &self.nodes[index] {
+                BvhNode::Leaf { first, count, .. } => {
+                    for &id in &self.order[*first..*first + *count] {
+                        if let Some(t) = ray.distance_to_aabb(&self.boxes[id as usize]) {
+                            if best.is_none_or(|(best_id, best_t)| t < best_t || (t == best_t && id < best_id)) {
+                                best = Some((id, t));
+                            }
+                        }
+                    }
+                }
+                BvhNode::Branch { left, right, .. } => {
+                    let near = ray.distance_to_aabb(self.nodes[*left].bounds()).map(|t| (*left, t));
+                    let far = ray.distance_to_aabb(self.nodes[*right].bounds()).map(|t| (*right, t));
+                    let (near, far) = match (near, far) {
+                        (Some(a), Some(b)) if b.1 < a.1 => (Some(b), Some(a)),
+                        pair => pair,
+                    };
+                    // Push the farther child first so the nearer one is popped next.
+                    stack.extend(far);
+                    stack.extend(near);
+                }
+            }
+        }
+
+        best
+    }
+
+    fn build_node(&mut self, first: usize, count: usize) -> usize {
+        let items = &mut self.order[first..first + count];
+        let boxes = &self.boxes;
+        let bounds = items
+            .iter()
+            .map(|&id| boxes[id as usize])
+            .reduce(|acc, aabb| acc.union(&aabb))
+            .unwrap();
+
+        let index = self.nodes.len();
+        if count <= MAX_LEAF_SIZE {
+            self.nodes.push(BvhNode::Leaf { bounds, first, count });
+            return index;
+        }
+
+        let centroid = |id: u32| {
+            let aabb = &boxes[id as usize];
+            math::center(aabb.min, aabb.max)
+        };
+        let mut lo = centroid(items[0]);
+        let mut hi = lo;
+        for &id in items.iter() {
+            let c = centroid(id);
+            lo = math::min(lo, c);
+            hi = math::max(hi, c);
+        }
+        let extent = hi - lo;
+        let axis = if extent.x >= extent.y && extent.x >= extent.z {
+            0
+        } else if extent.y >= extent.z {
+            1
+        } else {
+            2
+        };
+
+        let mid = count / 2;
+        items.select_nth_unstable_by(mid, |&a, &b| centroid(a)[axis].total_cmp(&centroid(b)[axis]));
+
+        // Reserve this node's slot before building children so the root stays at 0.
+        self.nodes.push(BvhNode::Leaf { bounds, first, count });
+        let left = self.build_node(first, mid);
+        let right = self.build_node(first + mid, count - mid);
+        self.nodes[index] = BvhNode::Branch { bounds, left, right };
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{lcg_unit, random_boxes};
+
+    #[test]
+    fn raycast_matches_brute_force() {
+        let boxes = random_boxes(11, 1000, 100.0, 5.0);
+        let flat: Vec<f32> = boxes
+            .iter()
+            .flat_map(|aabb| [aabb.min, aabb.max])
+            .flat_map(|corner| [corner.x, corner.y, corner.z])
+            .collect();
+        let bvh = Bvh::build(&flat);
+
+        let mut next = lcg_unit(12);
+        let mut hits = 0;
+        for _ in 0..500 {
+            let origin = [next(), next(), next()].map(|c| c * 120.0 - 60.0);
+            let direction = [next(), next(), next()].map(|c| c - 0.5);
+            let ray = Ray::new(origin[0], origin[1], origin[2], direction[0], direction[1], direction[2]);
+            let nearest = boxes.iter().filter_map(|aabb| ray.distance_to_aabb(aabb)).reduce(f32::min);
+
+            let got = bvh.raycast(&ray);
+            assert_eq!(got.map(|(_, t)| t), nearest);
+            if let Some((id, t)) = got {
+                // Ties go to any of the boxes at that distance.
+                assert_eq!(ray.distance_to_aabb(&boxes[id as usize]), Some(t));
+                hits += 1;
+            }
+        }
+        assert!(hits > 50, "{hits}");
+    }
+
+    #[test]
+    fn empty_bvh_misses() {
+        assert_eq!(Bvh::build(&[]).raycast(&Ray::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0)), None);
+    }
+}
@@ -0,0 +1,218 @@
+use wasm_bindgen::prelude::*;
+use crate::math::{self, Point3};
+use crate::{Ray, Sphere, AABB};
+
+/// Segment `p0`-`p1` swept by a sphere of `radius`.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct Capsule {
+    p0: Point3,
+    p1: Point3,
+    radius: f32,
+}
+
+#[wasm_bindgen]
+impl Capsule {
+    #[wasm_bindgen(constructor)]
+    pub fn new(p0_x: f32, p0_y: f32, p0_z: f32, p1_x: f32, p1_y: f32, p1_z: f32, radius: f32) -> Capsule {
+        Capsule {
+            p0: math::point(p0_x, p0_y, p0_z),
+            p1: math::point(p1_x, p1_y, p1_z),
+            radius,
+        }
+    }
+
+    pub fn intersects_aabb(&self, aabb: &AABB) -> bool {
+        segment_distance_squared(&self.p0, &self.p1, aabb) <= self.radius * self.radius
+    }
+}
+
+impl Capsule {
+    fn closest_on_segment(&self, point: &Point3) -> Point3 {
+        let axis = self.p1 - self.p0;
+        let length_squared = math::length_squared(axis);
+        if length_squared == 0.0 {
+            return self.p0;
+        }
+        let s = (math::dot(*point - self.p0, axis) / length_squared).clamp(0.0, 1.0);
+        self.p0 + axis * s
+    }
+}
+
+// Squared distance between a segment and a box. The distance from a point
+// moving linearly along the segment to a convex box is convex in the segment
+// parameter, so a ternary search converges on the minimum, including cases
+// where it sits at an endpoint or the segment passes a box edge or corner.
+pub(crate) fn segment_distance_squared(p0: &Point3, p1: &Point3, aabb: &AABB) -> f32 {
+    let distance_at = |s: f32| {
+        let point = *p0 + (*p1 - *p0) * s;
+        math::distance_squared(point, aabb.clamp_point(&point))
+    };
+
+    let (mut lo, mut hi) = (0.0f32, 1.0f32);
+    for _ in 0..64 {
+        let a = lo + (hi - lo) / 3.0;
+        let b = hi - (hi - lo) / 3.0;
+        if distance_at(a) <= distance_at(b) {
+            hi = b;
+        } else {
+            lo = a;
+        }
+    }
+
+    distance_at(0.0).min(distance_at(1.0)).min(distance_at((lo + hi) * 0.5))
+}
+
+#[wasm_bindgen]
+impl Ray {
+    /// Distance to the nearest hit on `capsule`, or `None` on a miss. A ray
+    /// starting inside reports `0.0`.
+    pub fn intersects_capsule(&self, capsule: &Capsule) -> Option<f32> {
+        let radius_squared = capsule.radius * capsule.radius;
+        if math::distance_squared(self.origin, capsule.closest_on_segment(&self.origin)) <= radius_squared {
+            return Some(0.0);
+        }
+
+        // The capsule is the union of its cylindrical body and two end
+        // spheres, so the first entry is the earliest entry into any of them.
+        let cap = |p: &Point3| self.intersects_sphere(&Sphere::new(p.x, p.y, p.z, capsule.radius));
+        let mut nearest = [cap(&capsule.p0), cap(&capsule.p1)]
+            .into_iter()
+            .flatten()
+            .reduce(f32::min);
+
+        let axis = capsule.p1 - capsule.p0;
+        let offset = self.origin - capsule.p0;
+        let axis_squared = math::length_squared(axis);
+        let axis_dir = math::dot(axis, self.direction);
+        let axis_offset = math::dot(axis, offset);
+
+        let a = axis_squared - axis_dir * axis_dir;
+        if a > f32::EPSILON * axis_squared {
+            let b = axis_squared * math::dot(offset, self.direction) - axis_offset * axis_dir;
+            let c = axis_squared * math::length_squared(offset)
+                - axis_offset * axis_offset
+                - radius_squared * axis_squared;
+            let discriminant = b * b - a * c;
+
+            if discriminant >= 0.0 {
+                let t = (-b - discriminant.sqrt()) / a;
+                let along = axis_offset + t * axis_dir;
+                if t >= 0.0 && along > 0.0 && along < axis_squared {
+                    nearest = Some(nearest.map_or(t, |n| n.min(t)));
+                }
+            }
+        }
+
+        nearest
+    }
+
+    /// Distance at which a sphere of `radius` moving along the ray first
+    /// touches `aabb`, i.e. the ray's entry into the box rounded outward by
+    /// `radius`. The expanded box is hit first; hits that land beyond an edge
+    /// or corner of the original box are refined against the capsules along
+    /// the nearby edges, so corners are properly rounded.
+    pub fn spherecast_aabb(&self, radius: f32, aabb: &AABB) -> Option<f32> {
+        if radius <= 0.0 {
+            return self.distance_to_aabb(aabb);
+        }
+
+        let t = self.distance_to_aabb(&aabb.expand(radius))?;
+        let hit = self.origin + self.direction * t;
+
+        // Side of the original box the hit lies beyond on each axis.
+        let side: [i32; 3] = std::array::from_fn(|axis| {
+            if hit[axis] < aabb.min[axis] {
+                -1
+            } else if hit[axis] > aabb.max[axis] {
+                1
+            } else {
+                0
+            }
+        });
+        let outside = side.iter().filter(|&&s| s != 0).count();
+        if outside < 2 {
+            return Some(t);
+        }
+
+        // In an edge region only that edge can be hit; in a corner region,
+        // any of the three edges meeting at the corner.
+        let mut corner = aabb.min;
+        for axis in 0..3 {
+            if side[axis] > 0 {
+                corner[axis] = aabb.max[axis];
+            }
+        }
+
+        (0..3)
+            .filter(|&axis| outside == 3 || side[axis] == 0)
+            .filter_map(|axis| {
+                let (mut p0, mut p1) = (corner, corner);
+                p0[axis] = aabb.min[axis];
+                p1[axis] = aabb.max[axis];
+                self.intersects_capsule(&Capsule { p0, p1, radius })
+            })
+            .reduce(f32::min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_flat_capsule_across_an_edge_touches_within_its_radius() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        // Segments along (1, -1, 0) passing `gap` from the edge x = y = 1.
+        let gap = 0.3f32;
+        let offset = 1.0 + gap / std::f32::consts::SQRT_2;
+        let across = |z: f32, radius: f32| {
+            Capsule::new(offset - 3.0, offset + 3.0, z, offset + 3.0, offset - 3.0, z, radius)
+        };
+        assert!(across(0.5, gap + 0.01).intersects_aabb(&aabb));
+        assert!(!across(0.5, gap - 0.01).intersects_aabb(&aabb));
+
+        // Lifted 0.2 above the box, the nearest point is the corner (1, 1, 1).
+        let corner = (gap * gap + 0.04f32).sqrt();
+        assert!(across(1.2, corner + 0.01).intersects_aabb(&aabb));
+        assert!(!across(1.2, corner - 0.01).intersects_aabb(&aabb));
+    }
+
+    #[test]
+    fn an_end_cap_reaches_the_box() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        assert!(!Capsule::new(3.0, 0.5, 0.5, 10.0, 0.5, 0.5, 1.9).intersects_aabb(&aabb));
+        assert!(Capsule::new(3.0, 0.5, 0.5, 10.0, 0.5, 0.5, 2.01).intersects_aabb(&aabb));
+    }
+
+    #[test]
+    fn spherecast_with_zero_radius_is_a_raycast() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        let rays = [
+            Ray::new(-4.0, 0.5, 0.5, 1.0, 0.0, 0.0),
+            Ray::new(-4.0, 1.2, 0.5, 1.0, 0.0, 0.0),
+            Ray::new(0.5, 0.5, 0.5, 0.0, 1.0, 0.0),
+            Ray::new(3.0, 3.0, 3.0, -1.0, -1.0, -1.0),
+        ];
+        for ray in rays {
+            assert_eq!(ray.spherecast_aabb(0.0, &aabb), ray.distance_to_aabb(&aabb));
+        }
+    }
+
+    #[test]
+    fn spherecast_turns_a_near_miss_into_a_hit() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        let ray = Ray::new(-4.0, 1.2, 0.5, 1.0, 0.0, 0.0);
+        assert_eq!(ray.distance_to_aabb(&aabb), None);
+        assert_eq!(ray.spherecast_aabb(0.1, &aabb), None);
+        // The sphere touches the edge at x = 0, y = 1 once its center is 0.5
+        // from it, not at the corner of the expanded box.
+        let t = ray.spherecast_aabb(0.5, &aabb).unwrap();
+        assert!((t - (4.0 - 0.21f32.sqrt())).abs() < 1e-5, "{t}");
+        let t = Ray::new(-4.0, 1.4, 0.5, 1.0, 0.0, 0.0).spherecast_aabb(0.5, &aabb).unwrap();
+        assert!((t - 3.7).abs() < 1e-5, "{t}");
+        // Head on, it touches once it is `radius` from the face.
+        assert_eq!(Ray::new(-4.0, 0.5, 0.5, 1.0, 0.0, 0.0).spherecast_aabb(0.5, &aabb), Some(3.5));
+        assert_eq!(Ray::new(-4.0, 1.6, 0.5, 1.0, 0.0, 0.0).spherecast_aabb(0.5, &aabb), None);
+    }
+}
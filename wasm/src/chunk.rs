@@ -0,0 +1,325 @@
+use wasm_bindgen::prelude::*;
+
+use crate::math::{self, Point3};
+use crate::AABB;
+
+/// Edge length, in voxels, of a chunk.
+pub const CHUNK_SIZE: u32 = 32;
+
+/// Fixed-size cube of voxel ids, where id 0 is air. Voxels are stored flat
+/// with x varying fastest, i.e. at index `x + size * (y + size * z)`. Each
+/// voxel spans `voxel_size` world units, which is 1 except for downsampled
+/// chunks.
+#[wasm_bindgen]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "ChunkData"))]
+pub struct Chunk {
+    origin: Point3,
+    size: u32,
+    voxel_size: f32,
+    voxels: Vec<u16>,
+    solid: u32,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    dirty: bool,
+}
+
+#[wasm_bindgen]
+impl Chunk {
+    /// Creates an all-air chunk whose minimum corner sits at the given world position.
+    #[wasm_bindgen(constructor)]
+    pub fn new(origin_x: f32, origin_y: f32, origin_z: f32) -> Chunk {
+        Chunk {
+            origin: math::point(origin_x, origin_y, origin_z),
+            size: CHUNK_SIZE,
+            voxel_size: 1.0,
+            voxels: vec![0; (CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE) as usize],
+            solid: 0,
+            dirty: false,
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// World-space edge length of one voxel.
+    #[wasm_bindgen(getter)]
+    pub fn voxel_size(&self) -> f32 {
+        self.voxel_size
+    }
+
+    /// Voxel id at the given local coordinates, or 0 (air) when out of range.
+    pub fn get(&self, x: i32, y: i32, z: i32) -> u16 {
+        self.index(x, y, z).map_or(0, |i| self.voxels[i])
+    }
+
+    /// Writes a voxel id, returning `false` and leaving the chunk untouched when
+    /// the coordinates are out of range.
+    pub fn set(&mut self, x: i32, y: i32, z: i32, id: u16) -> bool {
+        match self.index(x, y, z) {
+            Some(i) => {
+                self.solid = self.solid + (id != 0) as u32 - (self.voxels[i] != 0) as u32;
+                self.voxels[i] = id;
+                self.dirty = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn fill(&mut self, id: u16) {
+        self.voxels.fill(id);
+        self.solid = if id == 0 { 0 } else { self.voxels.len() as u32 };
+        self.dirty = true;
+    }
+
+    /// World-space box covered by the chunk.
+    pub fn bounds(&self) -> AABB {
+        let extent = self.size as f32 * self.voxel_size;
+        AABB::new(
+            self.origin.x,
+            self.origin.y,
+            self.origin.z,
+            self.origin.x + extent,
+            self.origin.y + extent,
+            self.origin.z + extent,
+        )
+    }
+
+    /// Pointer to the backing voxel buffer in wasm memory, valid until the
+    /// chunk is dropped. Pair with `len` to view it as a `Uint16Array`.
+    pub fn data_ptr(&self) -> *const u16 {
+        self.voxels.as_ptr()
+    }
+
+    /// Writable pointer to the backing voxel buffer, for editing voxels in
+    /// place from JS:
+    ///
+    /// ```js
+    /// const voxels = new Uint16Array(memory.buffer, chunk.data_mut_ptr(), chunk.len());
+    /// voxels[x + size * (y + size * z)] = id;
+    /// chunk.mark_dirty();
+    /// ```
+    ///
+    /// The view must be recreated whenever wasm memory grows, since that
+    /// detaches `memory.buffer`. Writes through it bypass `set`, so call
+    /// `mark_dirty` afterwards.
+    pub fn data_mut_ptr(&mut self) -> *mut u16 {
+        self.voxels.as_mut_ptr()
+    }
+
+    /// Number of voxels in the backing buffer.
+    pub fn len(&self) -> usize {
+        self.voxels.len()
+    }
+
+    /// Flags the chunk as needing a re-mesh and recounts its solid voxels.
+    /// `set`, `fill` and other edits made through the chunk's methods do this
+    /// automatically.
+    pub fn mark_dirty(&mut self) {
+        self.solid = count_solid(&self.voxels);
+        self.dirty = true;
+    }
+
+    /// Whether the voxels changed since the last `clear_dirty`.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Resets the dirty flag, typically right after meshing.
+    pub fn clear_dirty(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Number of non-air voxels.
+    pub fn solid_count(&self) -> u32 {
+        self.solid
+    }
+
+    /// Whether every voxel is air.
+    pub fn is_empty(&self) -> bool {
+        self.solid == 0
+    }
+
+    /// Non-air voxels as `x, y, z, id` per voxel, in storage order.
+    pub fn solid_voxels(&self) -> Vec<i32> {
+        if self.solid == 0 {
+            return Vec::new();
+        }
+
+        let size = self.size as usize;
+        let mut out = Vec::with_capacity(self.solid as usize * 4);
+        for (i, &id) in self.voxels.iter().enumerate().filter(|&(_, &id)| id != 0) {
+            out.extend([(i % size) as i32, (i / size % size) as i32, (i / (size * size)) as i32, id as i32]);
+        }
+        out
+    }
+}
+
+impl Chunk {
+    pub(crate) fn from_voxels(origin: Point3, size: u32, voxels: Vec<u16>) -> Chunk {
+        debug_assert_eq!(voxels.len(), (size * size * size) as usize);
+        let solid = count_solid(&voxels);
+        Chunk { origin, size, voxel_size: 1.0, voxels, solid, dirty: false }
+    }
+
+    pub(crate) fn set_origin(&mut self, origin: Point3) {
+        self.origin = origin;
+    }
+
+    pub(crate) fn set_voxel_size(&mut self, voxel_size: f32) {
+        self.voxel_size = voxel_size;
+    }
+
+    pub(crate) fn voxels(&self) -> &[u16] {
+        &self.voxels
+    }
+
+    pub(crate) fn voxels_mut(&mut self) -> &mut [u16] {
+        &mut self.voxels
+    }
+
+    pub(crate) fn index(&self, x: i32, y: i32, z: i32) -> Option<usize> {
+        let size = self.size as i32;
+        if (0..size).contains(&x) && (0..size).contains(&y) && (0..size).contains(&z) {
+            Some((x + size * (y + size * z)) as usize)
+        } else {
+            None
+        }
+    }
+}
+
+// Deserialized form of a `Chunk`, checked before it becomes one so a loaded
+// chunk always holds `size³` voxels and a solid count that matches them.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct ChunkData {
+    origin: Point3,
+    size: u32,
+    voxel_size: f32,
+    voxels: Vec<u16>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<ChunkData> for Chunk {
+    type Error = String;
+
+    fn try_from(data: ChunkData) -> Result<Chunk, String> {
+        let expected = (data.size as usize).checked_pow(3);
+        if expected != Some(data.voxels.len()) {
+            return Err(format!("chunk of size {} cannot hold {} voxels", data.size, data.voxels.len()));
+        }
+        if !(data.voxel_size.is_finite() && data.voxel_size > 0.0) {
+            return Err(format!("invalid voxel size {}", data.voxel_size));
+        }
+
+        let mut chunk = Chunk::from_voxels(data.origin, data.size, data.voxels);
+        chunk.voxel_size = data.voxel_size;
+        Ok(chunk)
+    }
+}
+
+fn count_solid(voxels: &[u16]) -> u32 {
+    voxels.iter().filter(|&&id| id != 0).count() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_and_fill_track_the_solid_count() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        chunk.set(0, 0, 0, 3);
+        chunk.set(0, 0, 0, 5);
+        chunk.set(1, 0, 0, 5);
+        assert_eq!(chunk.solid_count(), 2);
+        chunk.set(0, 0, 0, 0);
+        assert_eq!(chunk.solid_count(), 1);
+        assert!(!chunk.set(-1, 0, 0, 5));
+        chunk.fill(2);
+        assert_eq!(chunk.solid_count(), CHUNK_SIZE.pow(3));
+        chunk.fill(0);
+        assert!(chunk.is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_recomputes_solid() {
+        let mut chunk = Chunk::new(1.0, 2.0, 3.0);
+        chunk.set(1, 1, 1, 9);
+        chunk.set(2, 1, 1, 4);
+
+        let json = serde_json::to_string(&chunk).unwrap();
+        let loaded: Chunk = serde_json::from_str(&json).unwrap();
+        assert_eq!((loaded.get(1, 1, 1), loaded.get(2, 1, 1)), (9, 4));
+        assert_eq!(loaded.solid_count(), 2);
+        assert_eq!(loaded.bounds().min_x(), 1.0);
+        assert!(!loaded.is_dirty());
+
+        let stale = json.replace("\"solid\":2", "\"solid\":7");
+        assert_ne!(stale, json);
+        assert_eq!(serde_json::from_str::<Chunk>(&stale).unwrap().solid_count(), 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rejects_mismatched_voxel_count() {
+        let json = r#"{"origin":[0.0,0.0,0.0],"size":2,"voxel_size":1.0,"voxels":[1,0,0],"solid":1}"#;
+        assert!(serde_json::from_str::<Chunk>(json).is_err());
+        let json = r#"{"origin":[0.0,0.0,0.0],"size":1,"voxel_size":0.0,"voxels":[1],"solid":1}"#;
+        assert!(serde_json::from_str::<Chunk>(json).is_err());
+        let json = r#"{"origin":[0.0,0.0,0.0],"size":1,"voxel_size":2.0,"voxels":[1],"solid":0}"#;
+        assert_eq!(serde_json::from_str::<Chunk>(json).unwrap().solid_count(), 1);
+    }
+
+    #[test]
+    fn raw_buffer_writes_are_visible_to_get() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        let index = chunk.index(1, 2, 3).unwrap();
+        assert_eq!(index, 1 + 32 * (2 + 32 * 3));
+        unsafe {
+            *chunk.data_mut_ptr().add(index) = 7;
+            assert_eq!(*chunk.data_ptr().add(index), 7);
+        }
+        assert_eq!(chunk.get(1, 2, 3), 7);
+
+        // The chunk can't see the write until told about it.
+        assert!(!chunk.is_dirty());
+        assert_eq!(chunk.solid_count(), 0);
+        chunk.mark_dirty();
+        assert!(chunk.is_dirty());
+        assert_eq!(chunk.solid_count(), 1);
+        assert_eq!(chunk.solid_voxels(), vec![1, 2, 3, 7]);
+    }
+
+    #[test]
+    fn edits_through_methods_mark_the_chunk_dirty() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        assert!(chunk.set(0, 0, 0, 1));
+        assert!(chunk.is_dirty());
+        chunk.clear_dirty();
+        assert!(!chunk.set(32, 0, 0, 1));
+        assert!(!chunk.is_dirty());
+        chunk.fill(2);
+        assert!(chunk.is_dirty());
+    }
+
+    #[test]
+    fn solid_voxels_lists_scattered_voxels_in_storage_order() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        assert!(chunk.solid_voxels().is_empty());
+
+        chunk.set(31, 31, 31, 9);
+        chunk.set(5, 0, 0, 2);
+        chunk.set(0, 7, 1, 300);
+        chunk.set(4, 4, 4, 1);
+        chunk.set(4, 4, 4, 0);
+        assert_eq!(chunk.solid_voxels(), vec![5, 0, 0, 2, 0, 7, 1, 300, 31, 31, 31, 9]);
+
+        chunk.fill(0);
+        assert!(chunk.solid_voxels().is_empty());
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{math, Chunk};
+
+const FORMAT_VERSION: u8 = 2;
+
+// Version 1 predates the stored voxel size; its chunks are always 1 unit.
+const FORMAT_VERSION_UNSCALED: u8 = 1;
+
+// Largest chunk edge accepted when decoding, to bound the allocation made for
+// untrusted input.
+const MAX_DECODED_SIZE: u32 = 256;
+
+#[wasm_bindgen]
+impl Chunk {
+    /// Encodes the voxel ids as a palette plus run-length encoded palette
+    /// indices:
+    ///
+    /// - `u8` format version (currently 2)
+    /// - varint chunk size, voxel size as `f32` LE
+    /// - varint palette length, palette ids as `u16` LE
+    /// - `(varint palette index, varint run length)` pairs in storage order
+    ///
+    /// The chunk origin is not stored.
+    pub fn to_compressed(&self) -> Vec<u8> {
+        let mut palette = Vec::new();
+        let mut lookup = HashMap::new();
+        let mut runs: Vec<(u32, u32)> = Vec::new();
+
+        for &id in self.voxels() {
+            let index = *lookup.entry(id).or_insert_with(|| {
+                palette.push(id);
+                palette.len() as u32 - 1
+            });
+
+            match runs.last_mut() {
+                Some((last, length)) if *last == index => *length += 1,
+                _ => runs.push((index, 1)),
+            }
+        }
+
+        let mut out = vec![FORMAT_VERSION];
+        write_varint(&mut out, self.size());
+        out.extend_from_slice(&self.voxel_size().to_le_bytes());
+        write_varint(&mut out, palette.len() as u32);
+        for id in palette {
+            out.extend_from_slice(&id.to_le_bytes());
+        }
+        for (index, length) in runs {
+            write_varint(&mut out, index);
+            write_varint(&mut out, length);
+        }
+        out
+    }
+
+    /// Decodes the output of `to_compressed` into a chunk at the origin, or
+    /// returns `None` if the bytes are malformed or use an unknown version.
+    /// Version 1 data, which has no voxel size, decodes with a voxel size of 1.
+    pub fn from_compressed(bytes: &[u8]) -> Option<Chunk> {
+        let (&version, mut rest) = bytes.split_first()?;
+        if version != FORMAT_VERSION && version != FORMAT_VERSION_UNSCALED {
+            return None;
+        }
+
+        let size = read_varint(&mut rest)?;
+        if size == 0 || size > MAX_DECODED_SIZE {
+            return None;
+        }
+        let voxel_size = if version == FORMAT_VERSION_UNSCALED {
+            1.0
+        } else {
+            let (scale, tail) = rest.split_first_chunk::<4>()?;
+            rest = tail;
+            f32::from_le_bytes(*scale)
+        };
+        if !(voxel_size.is_finite() && voxel_size > 0.0) {
+            return None;
+        }
+        let volume = (size * size * size) as usize;
+
+        let palette_len = read_varint(&mut rest)? as usize;
+        let palette_size = palette_len.checked_mul(2)?;
+        if rest.len() < palette_size {
+            return None;
+        }
+        let (palette_bytes, mut rest) = rest.split_at(palette_size);
+        let palette: Vec<u16> = palette_bytes
+            .chunks_exact(2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+            .collect();
+
+        let mut voxels = Vec::with_capacity(volume);
+        while !rest.is_empty() {
+            let id = *palette.get(read_varint(&mut rest)? as usize)?;
+            let length = read_varint(&mut rest)? as usize;
+            if length > volume - voxels.len() {
+                return None;
+            }
+            voxels.resize(voxels.len() + length, id);
+        }
+
+        if voxels.len() != volume {
+            return None;
+        }
+        let mut chunk = Chunk::from_voxels(math::point(0.0, 0.0, 0.0), size, voxels);
+        chunk.set_voxel_size(voxel_size);
+        Some(chunk)
+    }
+}
+
+// LEB128 unsigned varints, shared with the region format.
+pub(crate) fn write_varint(out: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+pub(crate) fn read_varint(bytes: &mut &[u8]) -> Option<u32> {
+    let mut value = 0u32;
+    for shift in (0..35).step_by(7) {
+        let (&byte, rest) = bytes.split_first()?;
+        *bytes = rest;
+        // The fifth byte holds only the top 4 bits of a `u32`.
+        if shift == 28 && byte & 0x70 != 0 {
+            return None;
+        }
+        value |= ((byte & 0x7f) as u32) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::lcg;
+
+    #[test]
+    fn voxel_size_survives_the_round_trip() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        chunk.set(3, 4, 5, 9);
+        let lod = chunk.downsample();
+
+        let decoded = Chunk::from_compressed(&lod.to_compressed()).unwrap();
+        assert_eq!((decoded.size(), decoded.voxel_size()), (16, 2.0));
+        assert_eq!(decoded.bounds().max_x(), 32.0);
+    }
+
+    #[test]
+    fn version_1_decodes_unscaled() {
+        // Size 2, one palette entry, a single run of 8.
+        let chunk = Chunk::from_compressed(&[1, 2, 1, 7, 0, 0, 8]).unwrap();
+        assert_eq!((chunk.size(), chunk.voxel_size(), chunk.get(1, 1, 1)), (2, 1.0, 7));
+
+        let mut bad_scale = vec![FORMAT_VERSION, 2];
+        bad_scale.extend_from_slice(&(-1.0f32).to_le_bytes());
+        bad_scale.extend_from_slice(&[1, 7, 0, 0, 8]);
+        assert!(Chunk::from_compressed(&bad_scale).is_none());
+    }
+
+    #[test]
+    fn random_chunk_round_trips() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        let mut next = lcg(1);
+        for _ in 0..3000 {
+            let v = next();
+            chunk.set((v % 32) as i32, (v >> 5 & 31) as i32, (v >> 10 & 31) as i32, (v >> 15) as u16 % 300);
+        }
+
+        let decoded = Chunk::from_compressed(&chunk.to_compressed()).unwrap();
+        assert_eq!(decoded.voxels(), chunk.voxels());
+        assert_eq!(decoded.solid_count(), chunk.solid_count());
+    }
+
+    #[test]
+    fn single_id_chunk_is_tiny() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        assert!(chunk.to_compressed().len() < 16);
+        chunk.fill(7);
+        let bytes = chunk.to_compressed();
+        assert!(bytes.len() < 16);
+        let decoded = Chunk::from_compressed(&bytes).unwrap();
+        assert_eq!((decoded.get(0, 0, 0), decoded.get(31, 31, 31), decoded.solid_count()), (7, 7, 32768));
+    }
+
+    #[test]
+    fn malformed_input_is_rejected() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        chunk.set(1, 2, 3, 4);
+        let bytes = chunk.to_compressed();
+        assert!(Chunk::from_compressed(&bytes).is_some());
+
+        for len in 0..bytes.len() {
+            assert!(Chunk::from_compressed(&bytes[..len]).is_none(), "truncated to {len}");
+        }
+        // Runs past the end of the chunk.
+        let mut overlong = bytes.clone();
+        overlong.extend_from_slice(&[0, 1]);
+        assert!(Chunk::from_compressed(&overlong).is_none());
+        // Unknown version, zero size, oversized chunk.
+        assert!(Chunk::from_compressed(&[3]).is_none());
+        assert!(Chunk::from_compressed(&[FORMAT_VERSION, 0]).is_none());
+        let oversized = [FORMAT_VERSION_UNSCALED, 0x81, 0x02, 1, 0, 0, 0x80, 0x80, 0x80, 0x08];
+        assert!(Chunk::from_compressed(&oversized).is_none());
+        // A run referring past the palette, and a varint that never ends.
+        assert!(Chunk::from_compressed(&[FORMAT_VERSION_UNSCALED, 1, 1, 7, 0, 1, 1]).is_none());
+        assert!(Chunk::from_compressed(&[FORMAT_VERSION_UNSCALED, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]).is_none());
+    }
+
+    #[test]
+    fn varints_past_32_bits_are_rejected() {
+        let mut out = Vec::new();
+        write_varint(&mut out, u32::MAX);
+        assert_eq!(out, [0xff, 0xff, 0xff, 0xff, 0x0f]);
+        assert_eq!(read_varint(&mut out.as_slice()), Some(u32::MAX));
+        assert_eq!(read_varint(&mut [0xff, 0xff, 0xff, 0xff, 0x1f].as_slice()), None);
+        assert_eq!(read_varint(&mut [0x80, 0x80, 0x80, 0x80, 0x70].as_slice()), None);
+        assert_eq!(read_varint(&mut [0x80, 0x80, 0x80, 0x80, 0x80, 0x00].as_slice()), None);
+    }
+}
@@ -0,0 +1,76 @@
+use wasm_bindgen::prelude::*;
+
+use crate::capsule::segment_distance_squared;
+use crate::math::{self, Point3, Vector3};
+use crate::{point3, AABB};
+
+/// Solid cone opening from `apex` along `axis` with half-angle `angle`
+/// (radians), capped by a flat base `height` from the apex.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct Cone {
+    apex: Point3,
+    axis: Vector3,
+    height: f32,
+    angle: f32,
+}
+
+#[wasm_bindgen]
+impl Cone {
+    /// `apex` and `axis` are 3 floats each; the axis is normalized.
+    #[wasm_bindgen(constructor)]
+    pub fn new(apex: &[f32], axis: &[f32], height: f32, angle: f32) -> Cone {
+        Cone {
+            apex: point3(apex),
+            axis: math::to_vector(point3(axis)).normalize(),
+            height,
+            angle,
+        }
+    }
+
+    pub fn contains_point(&self, x: f32, y: f32, z: f32) -> bool {
+        let offset = math::point(x, y, z) - self.apex;
+        let along = math::dot(offset, self.axis);
+        if !(0.0..=self.height).contains(&along) {
+            return false;
+        }
+
+        let radius = along * self.angle.tan();
+        math::length_squared(offset - self.axis * along) <= radius * radius
+    }
+
+    /// Conservative test against the capsule around the cone's axis with the
+    /// base radius, so boxes near the apex or past the base rim may be
+    /// reported as intersecting.
+    pub fn intersects_aabb(&self, aabb: &AABB) -> bool {
+        let base = self.apex + self.axis * self.height;
+        let radius = self.height * self.angle.tan();
+        segment_distance_squared(&self.apex, &base, aabb) <= radius * radius
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_inside_the_half_angle_are_contained() {
+        let cone = Cone::new(&[0.0, 0.0, 0.0], &[0.0, 0.0, 2.0], 10.0, std::f32::consts::FRAC_PI_4);
+        assert!(cone.contains_point(0.0, 0.0, 0.0));
+        assert!(cone.contains_point(0.0, 4.9, 5.0));
+        assert!(!cone.contains_point(0.0, 5.1, 5.0));
+        assert!(cone.contains_point(-3.5, 3.5, 5.0));
+        assert!(!cone.contains_point(0.0, 0.0, -0.1));
+        assert!(!cone.contains_point(0.0, 0.0, 10.1));
+
+        let narrow = Cone::new(&[0.0, 0.0, 0.0], &[0.0, 0.0, 1.0], 10.0, 0.1);
+        assert!(!narrow.contains_point(0.0, 4.9, 5.0));
+    }
+
+    #[test]
+    fn intersects_aabb_is_a_bound_around_the_cone() {
+        let cone = Cone::new(&[0.0, 0.0, 0.0], &[0.0, 0.0, 1.0], 10.0, std::f32::consts::FRAC_PI_4);
+        assert!(cone.intersects_aabb(&AABB::new(-1.0, -1.0, 4.0, 1.0, 1.0, 6.0)));
+        assert!(!cone.intersects_aabb(&AABB::new(20.0, 20.0, 0.0, 21.0, 21.0, 1.0)));
+    }
+}
@@ -0,0 +1,97 @@
+use wasm_bindgen::prelude::*;
+
+use crate::Chunk;
+
+// Voxel-wise boolean edits. Cells are matched by local coordinates, so the
+// chunks' origins don't matter, but their sizes must agree: each operation
+// returns `false` and leaves the chunk untouched otherwise.
+#[wasm_bindgen]
+impl Chunk {
+    /// Copies every non-air voxel of `other` into this chunk.
+    pub fn union_with(&mut self, other: &Chunk) -> bool {
+        self.combine(other, |own, theirs| if theirs != 0 { theirs } else { own })
+    }
+
+    /// Clears every voxel that is non-air in `other`.
+    pub fn subtract(&mut self, other: &Chunk) -> bool {
+        self.combine(other, |own, theirs| if theirs != 0 { 0 } else { own })
+    }
+
+    /// Clears every voxel that is air in `other`, keeping this chunk's ids
+    /// where both are solid.
+    pub fn intersect_with(&mut self, other: &Chunk) -> bool {
+        self.combine(other, |own, theirs| if theirs != 0 { own } else { 0 })
+    }
+}
+
+impl Chunk {
+    fn combine(&mut self, other: &Chunk, op: impl Fn(u16, u16) -> u16) -> bool {
+        if self.size() != other.size() {
+            return false;
+        }
+
+        for (own, &theirs) in self.voxels_mut().iter_mut().zip(other.voxels()) {
+            *own = op(*own, theirs);
+        }
+        self.mark_dirty();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Chunk with `id` in every cell whose coordinate on `axis` is below 16.
+    fn half_filled(axis: usize, id: u16) -> Chunk {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        for x in 0..32 {
+            for y in 0..32 {
+                for z in 0..32 {
+                    if [x, y, z][axis] < 16 {
+                        chunk.set(x, y, z, id);
+                    }
+                }
+            }
+        }
+        chunk
+    }
+
+    #[test]
+    fn union_keeps_both_halves_and_prefers_the_other_chunk() {
+        let mut chunk = half_filled(0, 1);
+        assert!(chunk.union_with(&half_filled(1, 2)));
+        assert_eq!(chunk.solid_count(), 3 * 8192);
+        assert_eq!((chunk.get(0, 0, 0), chunk.get(0, 31, 0), chunk.get(31, 0, 0), chunk.get(31, 31, 0)), (2, 1, 2, 0));
+    }
+
+    #[test]
+    fn subtract_clears_the_overlap() {
+        let mut chunk = half_filled(0, 1);
+        chunk.clear_dirty();
+        assert!(chunk.subtract(&half_filled(1, 2)));
+        assert!(chunk.is_dirty());
+        assert_eq!(chunk.solid_count(), 8192);
+        assert_eq!((chunk.get(0, 0, 0), chunk.get(0, 31, 0), chunk.get(31, 31, 0)), (0, 1, 0));
+    }
+
+    #[test]
+    fn intersect_keeps_the_overlap_with_its_own_ids() {
+        let mut chunk = half_filled(0, 1);
+        assert!(chunk.intersect_with(&half_filled(1, 2)));
+        assert_eq!(chunk.solid_count(), 8192);
+        assert_eq!((chunk.get(0, 0, 0), chunk.get(0, 31, 0), chunk.get(31, 0, 0)), (1, 0, 0));
+    }
+
+    #[test]
+    fn mismatched_sizes_leave_the_chunk_untouched() {
+        let mut chunk = half_filled(0, 1);
+        chunk.clear_dirty();
+        let smaller = half_filled(1, 2).downsample();
+        assert!(!chunk.union_with(&smaller));
+        assert!(!chunk.subtract(&smaller));
+        assert!(!chunk.intersect_with(&smaller));
+        assert!(!chunk.is_dirty());
+        assert_eq!(chunk.solid_count(), 16384);
+    }
+}
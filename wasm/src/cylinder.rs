@@ -0,0 +1,75 @@
+use wasm_bindgen::prelude::*;
+
+use crate::capsule::segment_distance_squared;
+use crate::math::{self, Point3, Vector3};
+use crate::{point3, AABB};
+
+/// Solid cylinder rising `height` along `axis` from the center of its base.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct Cylinder {
+    base: Point3,
+    axis: Vector3,
+    height: f32,
+    radius: f32,
+}
+
+#[wasm_bindgen]
+impl Cylinder {
+    /// `base` and `axis` are 3 floats each; the axis is normalized.
+    #[wasm_bindgen(constructor)]
+    pub fn new(base: &[f32], axis: &[f32], height: f32, radius: f32) -> Cylinder {
+        Cylinder {
+            base: point3(base),
+            axis: math::to_vector(point3(axis)).normalize(),
+            height,
+            radius,
+        }
+    }
+
+    pub fn contains_point(&self, x: f32, y: f32, z: f32) -> bool {
+        let offset = math::point(x, y, z) - self.base;
+        let along = math::dot(offset, self.axis);
+        let radial = offset - self.axis * along;
+        (0.0..=self.height).contains(&along) && math::length_squared(radial) <= self.radius * self.radius
+    }
+
+    /// Conservative test against the capsule enclosing the cylinder, so boxes
+    /// just past the rims of the flat ends may be reported as intersecting.
+    pub fn intersects_aabb(&self, aabb: &AABB) -> bool {
+        let top = self.base + self.axis * self.height;
+        segment_distance_squared(&self.base, &top, aabb) <= self.radius * self.radius
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upright_cylinder_bounds_radius_and_height() {
+        let cylinder = Cylinder::new(&[0.0, 0.0, 0.0], &[0.0, 2.0, 0.0], 4.0, 1.0);
+        assert!(cylinder.contains_point(0.5, 2.0, 0.5));
+        assert!(cylinder.contains_point(0.0, 4.0, 1.0));
+        assert!(!cylinder.contains_point(0.8, 2.0, 0.8));
+        assert!(!cylinder.contains_point(0.0, 4.1, 0.0));
+        assert!(!cylinder.contains_point(0.0, -0.1, 0.0));
+    }
+
+    #[test]
+    fn tilted_cylinder_measures_off_axis_points_radially() {
+        let cylinder = Cylinder::new(&[0.0, 0.0, 0.0], &[1.0, 1.0, 0.0], 10.0, 0.5);
+        assert!(cylinder.contains_point(3.0, 3.0, 0.0));
+        // 1 / sqrt(2) from the axis, past the radius.
+        assert!(!cylinder.contains_point(3.0, 2.0, 0.0));
+        assert!(cylinder.contains_point(3.0, 2.5, 0.0));
+        assert!(!cylinder.contains_point(-0.1, -0.1, 0.0));
+    }
+
+    #[test]
+    fn intersects_aabb_reaches_the_side() {
+        let cylinder = Cylinder::new(&[0.0, 0.0, 0.0], &[0.0, 1.0, 0.0], 4.0, 1.0);
+        assert!(cylinder.intersects_aabb(&AABB::new(0.9, 0.0, 0.0, 2.0, 1.0, 1.0)));
+        assert!(!cylinder.intersects_aabb(&AABB::new(1.1, 0.0, 1.1, 2.0, 1.0, 2.0)));
+    }
+}
@@ -0,0 +1,114 @@
+use wasm_bindgen::prelude::*;
+
+use crate::Chunk;
+
+#[wasm_bindgen]
+impl Chunk {
+    /// Cells 6-connected to the seed that share its voxel id, as `x, y, z` per
+    /// cell with the seed first. An out-of-range seed yields nothing.
+    pub fn flood_fill(&self, x: i32, y: i32, z: i32) -> Vec<i32> {
+        let size = self.size() as usize;
+        self.connected_region(x, y, z)
+            .into_iter()
+            .flat_map(|i| [i % size, i / size % size, i / (size * size)].map(|c| c as i32))
+            .collect()
+    }
+
+    /// Replaces the seed's connected region with `new_id`, returning how many
+    /// voxels were written.
+    pub fn flood_fill_replace(&mut self, x: i32, y: i32, z: i32, new_id: u16) -> usize {
+        let region = self.connected_region(x, y, z);
+        let voxels = self.voxels_mut();
+        for &i in &region {
+            voxels[i] = new_id;
+        }
+        if !region.is_empty() {
+            self.mark_dirty();
+        }
+        region.len()
+    }
+}
+
+impl Chunk {
+    // Indices of the seed's region, gathered with an explicit stack so a
+    // chunk-sized region can't overflow the call stack.
+    fn connected_region(&self, x: i32, y: i32, z: i32) -> Vec<usize> {
+        let Some(seed) = self.index(x, y, z) else {
+            return Vec::new();
+        };
+
+        let id = self.voxels()[seed];
+        let mut visited = vec![false; self.len()];
+        let mut stack = vec![[x, y, z]];
+        let mut region = Vec::new();
+        visited[seed] = true;
+
+        while let Some(cell) = stack.pop() {
+            region.push(self.index(cell[0], cell[1], cell[2]).unwrap());
+
+            for axis in 0..3 {
+                for step in [-1, 1] {
+                    let mut next = cell;
+                    next[axis] += step;
+                    if let Some(i) = self.index(next[0], next[1], next[2]) {
+                        if !visited[i] && self.voxels()[i] == id {
+                            visited[i] = true;
+                            stack.push(next);
+                        }
+                    }
+                }
+            }
+        }
+
+        region
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A 5x5x5 shell of id 5 from 2 to 6 on every axis, hollow inside.
+    fn hollow_shell() -> Chunk {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        for x in 2..7 {
+            for y in 2..7 {
+                for z in 2..7 {
+                    if [x, y, z].iter().any(|&c| c == 2 || c == 6) {
+                        chunk.set(x, y, z, 5);
+                    }
+                }
+            }
+        }
+        chunk
+    }
+
+    #[test]
+    fn an_empty_chunk_fills_completely() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        let cells = chunk.flood_fill(31, 0, 31);
+        assert_eq!(cells.len(), 3 * chunk.len());
+        assert_eq!(&cells[..3], &[31, 0, 31]);
+        assert_eq!(chunk.flood_fill_replace(0, 0, 0, 4), chunk.len());
+        assert_eq!(chunk.solid_count() as usize, chunk.len());
+        assert!(chunk.flood_fill(-1, 0, 0).is_empty());
+        assert!(chunk.flood_fill(0, 32, 0).is_empty());
+    }
+
+    #[test]
+    fn a_hollow_shell_separates_inside_from_outside() {
+        let mut chunk = hollow_shell();
+        let inside = chunk.flood_fill(4, 4, 4);
+        assert_eq!(inside.len(), 27 * 3);
+        assert_eq!(&inside[..3], &[4, 4, 4]);
+        assert!(inside.chunks(3).all(|c| c.iter().all(|v| (3..6).contains(v))));
+        assert_eq!(chunk.flood_fill(2, 2, 2).len() / 3, 125 - 27);
+
+        chunk.clear_dirty();
+        assert_eq!(chunk.flood_fill_replace(4, 4, 4, 9), 27);
+        assert!(chunk.is_dirty());
+        assert_eq!((chunk.get(5, 5, 5), chunk.get(2, 4, 4), chunk.get(0, 0, 0)), (9, 5, 0));
+        assert_eq!(chunk.flood_fill_replace(0, 0, 0, 1), chunk.len() - 125);
+        assert_eq!(chunk.get(4, 4, 4), 9);
+    }
+}
@@ -0,0 +1,136 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{Plane, AABB};
+
+/// View frustum as six inward-facing planes: left, right, bottom, top, near, far.
+#[wasm_bindgen]
+pub struct Frustum {
+    planes: [Plane; 6],
+}
+
+#[wasm_bindgen]
+impl Frustum {
+    /// Extracts the planes from a view-projection matrix given as 16 floats in
+    /// column-major order (the gl-matrix `mat4` layout), with clip-space depth
+    /// in `[-1, 1]`. Matrices with `[0, 1]` depth still cull correctly, only
+    /// with a slightly looser near plane. Returns `None` unless `m` holds
+    /// exactly 16 floats.
+    pub fn from_matrix(m: &[f32]) -> Option<Frustum> {
+        if m.len() != 16 {
+            return None;
+        }
+
+        let row = |i: usize| [m[i], m[4 + i], m[8 + i], m[12 + i]];
+        let (r0, r1, r2, r3) = (row(0), row(1), row(2), row(3));
+        let plane = |a: [f32; 4], b: [f32; 4], sign: f32| {
+            Plane::new(
+                a[0] + sign * b[0],
+                a[1] + sign * b[1],
+                a[2] + sign * b[2],
+                a[3] + sign * b[3],
+            )
+        };
+
+        Some(Frustum {
+            planes: [
+                plane(r3, r0, 1.0),
+                plane(r3, r0, -1.0),
+                plane(r3, r1, 1.0),
+                plane(r3, r1, -1.0),
+                plane(r3, r2, 1.0),
+                plane(r3, r2, -1.0),
+            ],
+        })
+    }
+
+    /// The eight corners as 24 floats, `x, y, z` per corner, found by
+    /// intersecting plane triples. Corner `i` lies on the right plane when
+    /// bit 0 of `i` is set (else the left), the top for bit 1 (else the
+    /// bottom) and the far for bit 2 (else the near), matching the order of
+    /// `AABB::corners`.
+    pub fn corners(&self) -> Vec<f32> {
+        let [left, right, bottom, top, near, far] = &self.planes;
+        (0..8)
+            .map(|i| {
+                let x = if i & 1 == 0 { left } else { right };
+                let y = if i & 2 == 0 { bottom } else { top };
+                let z = if i & 4 == 0 { near } else { far };
+                Plane::meet(x, y, z)
+            })
+            .flat_map(|corner| [corner.x, corner.y, corner.z])
+            .collect()
+    }
+
+    /// The 12 edges between `corners` as pairs of corner indices, for drawing
+    /// the frustum with `gl.LINES`.
+    pub fn line_indices(&self) -> Vec<u32> {
+        (0..8u32)
+            .flat_map(|i| [1, 2, 4].into_iter().filter(move |bit| i & bit == 0).flat_map(move |bit| [i, i | bit]))
+            .collect()
+    }
+
+    pub fn intersects_aabb(&self, aabb: &AABB) -> bool {
+        self.planes.iter().all(|plane| plane.classify_aabb(aabb) >= 0)
+    }
+
+    /// Tests boxes given as `min_x, min_y, min_z, max_x, max_y, max_z` per box,
+    /// returning 1 for each visible box and 0 for each culled one.
+    pub fn cull(&self, boxes_flat: &[f32]) -> Vec<u8> {
+        boxes_flat
+            .chunks_exact(6)
+            .map(|b| {
+                let aabb = AABB::new(b[0], b[1], b[2], b[3], b[4], b[5]);
+                self.intersects_aabb(&aabb) as u8
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IDENTITY: [f32; 16] = [1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0];
+
+    #[test]
+    fn identity_frustum_corners_are_the_clip_cube() {
+        let corners = Frustum::from_matrix(&IDENTITY).unwrap().corners();
+        let cube = AABB::new(-1.0, -1.0, -1.0, 1.0, 1.0, 1.0).corners();
+        assert!(corners.iter().zip(&cube).all(|(a, b)| (a - b).abs() < 1e-6), "{corners:?}");
+
+        // Scaling x by 1/2 and y by 1/4 widens the box it sees.
+        let mut scaled = IDENTITY;
+        (scaled[0], scaled[5]) = (0.5, 0.25);
+        let corners = Frustum::from_matrix(&scaled).unwrap().corners();
+        assert!((corners[21] - 2.0).abs() < 1e-6 && (corners[22] - 4.0).abs() < 1e-6, "{corners:?}");
+    }
+
+    #[test]
+    fn line_indices_join_corners_differing_in_one_bit() {
+        let indices = Frustum::from_matrix(&IDENTITY).unwrap().line_indices();
+        assert_eq!(indices.len(), 24);
+        assert!(indices.chunks(2).all(|edge| (edge[0] ^ edge[1]).count_ones() == 1));
+        let mut edges: Vec<_> = indices.chunks(2).map(|edge| (edge[0], edge[1])).collect();
+        edges.sort_unstable();
+        edges.dedup();
+        assert_eq!(edges.len(), 12);
+    }
+
+    #[test]
+    fn identity_frustum_culls_outside_the_cube() {
+        let frustum = Frustum::from_matrix(&IDENTITY).unwrap();
+        assert!(frustum.intersects_aabb(&AABB::new(-0.5, -0.5, -0.5, 0.5, 0.5, 0.5)));
+        assert!(frustum.intersects_aabb(&AABB::new(0.9, 0.9, 0.9, 3.0, 3.0, 3.0)));
+        assert!(!frustum.intersects_aabb(&AABB::new(1.1, 0.0, 0.0, 3.0, 1.0, 1.0)));
+        assert_eq!(frustum.cull(&[0.0, 0.0, 0.0, 0.1, 0.1, 0.1, 5.0, 5.0, 5.0, 6.0, 6.0, 6.0]), vec![1, 0]);
+    }
+
+    #[test]
+    fn from_matrix_rejects_the_wrong_size_and_tolerates_a_zero_matrix() {
+        assert!(Frustum::from_matrix(&IDENTITY[..12]).is_none());
+        assert!(Frustum::from_matrix(&[0.0; 17]).is_none());
+        // Every plane of the zero matrix is degenerate, so nothing is culled.
+        let zero = Frustum::from_matrix(&[0.0; 16]).unwrap();
+        assert!(zero.intersects_aabb(&AABB::new(100.0, 100.0, 100.0, 101.0, 101.0, 101.0)));
+    }
+}
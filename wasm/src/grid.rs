@@ -0,0 +1,167 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{math, Chunk, AABB};
+
+/// Most cells `covered_voxels` lists; a box covering more yields no cells.
+pub const MAX_COVERED_VOXELS: u64 = 1 << 20;
+
+/// Voxel indices of world positions given as `x, y, z` per point. Each
+/// coordinate is divided by `voxel_size` and floored, so negative positions
+/// land in negative cells: `-0.1` maps to `-1`, not `0`. Results outside the
+/// `i32` range saturate.
+#[wasm_bindgen]
+pub fn world_to_voxel(points_flat: &[f32], voxel_size: f32) -> Vec<i32> {
+    points_flat.chunks_exact(3).flatten().map(|&c| (c / voxel_size).floor() as i32).collect()
+}
+
+/// World-space centers of voxel cells given as `x, y, z` per cell, the
+/// inverse of `world_to_voxel` up to the position within the cell.
+#[wasm_bindgen]
+pub fn voxel_to_world(cells_flat: &[i32], voxel_size: f32) -> Vec<f32> {
+    cells_flat.chunks_exact(3).flatten().map(|&c| (c as f32 + 0.5) * voxel_size).collect()
+}
+
+#[wasm_bindgen]
+impl AABB {
+    /// Cells of a grid of `voxel_size` cubes that the box overlaps, as
+    /// `x, y, z` per cell with x varying fastest. The range runs from the
+    /// floor of `min` to the ceiling of `max`, so a box inside one cell
+    /// covers exactly that cell and a box ending on a cell boundary does not
+    /// reach into the next one. Boxes covering more than
+    /// `MAX_COVERED_VOXELS` cells yield an empty list.
+    pub fn covered_voxels(&self, voxel_size: f32) -> Vec<i32> {
+        let Some((lo, hi)) = cell_range(self, voxel_size) else {
+            return Vec::new();
+        };
+        if cell_count(lo, hi).is_none_or(|count| count > MAX_COVERED_VOXELS) {
+            return Vec::new();
+        }
+
+        let mut cells = Vec::new();
+        for z in lo[2]..hi[2] {
+            for y in lo[1]..hi[1] {
+                for x in lo[0]..hi[0] {
+                    cells.extend([x, y, z]);
+                }
+            }
+        }
+        cells
+    }
+}
+
+#[wasm_bindgen]
+impl Chunk {
+    /// Sets every voxel the world-space box covers, per `covered_voxels`,
+    /// to `id`, returning how many voxels inside the chunk were written.
+    pub fn stamp_aabb(&mut self, aabb: &AABB, id: u16) -> usize {
+        let origin = math::to_vector(self.bounds().min);
+        let local = AABB { min: aabb.min - origin, max: aabb.max - origin };
+        let Some((lo, hi)) = cell_range(&local, self.voxel_size()) else {
+            return 0;
+        };
+
+        let size = self.size() as i32;
+        let (lo, hi) = (lo.map(|c| c.max(0)), hi.map(|c| c.min(size)));
+        let mut written = 0;
+        for z in lo[2]..hi[2] {
+            for y in lo[1]..hi[1] {
+                for x in lo[0]..hi[0] {
+                    self.set(x, y, z, id);
+                    written += 1;
+                }
+            }
+        }
+        written
+    }
+}
+
+// Half-open cell range `lo..hi` per axis covered by the box, widened to one
+// cell on axes where the box has no extent. `None` for an inverted or
+// unbounded box, or a voxel size that isn't a positive finite number.
+fn cell_range(aabb: &AABB, voxel_size: f32) -> Option<([i32; 3], [i32; 3])> {
+    if !(aabb.is_valid() && voxel_size.is_finite() && voxel_size > 0.0) {
+        return None;
+    }
+
+    let lo = std::array::from_fn::<_, 3, _>(|axis| (aabb.min[axis] / voxel_size).floor());
+    let hi = std::array::from_fn::<_, 3, _>(|axis| (aabb.max[axis] / voxel_size).ceil());
+    if !lo.iter().chain(&hi).all(|c| c.is_finite()) {
+        return None;
+    }
+
+    let lo = lo.map(|c| c as i32);
+    let hi = std::array::from_fn(|axis| (hi[axis] as i32).max(lo[axis].saturating_add(1)));
+    Some((lo, hi))
+}
+
+// Number of cells in the range from `cell_range`, or `None` if it doesn't fit
+// in a `u64`.
+fn cell_count(lo: [i32; 3], hi: [i32; 3]) -> Option<u64> {
+    (0..3).try_fold(1u64, |count, axis| count.checked_mul((hi[axis] as i64 - lo[axis] as i64) as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world_to_voxel_floors_negative_positions() {
+        assert_eq!(world_to_voxel(&[-0.1, 0.0, 0.99, -1.0, -1.01, 5.5], 1.0), vec![-1, 0, 0, -1, -2, 5]);
+        assert_eq!(world_to_voxel(&[-0.1, 1.0, 3.9], 2.0), vec![-1, 0, 1]);
+        assert_eq!(voxel_to_world(&[-1, 0, 2], 2.0), vec![-1.0, 1.0, 5.0]);
+        let cells = vec![-3, 0, 7];
+        assert_eq!(world_to_voxel(&voxel_to_world(&cells, 0.5), 0.5), cells);
+    }
+
+    #[test]
+    fn world_to_voxel_saturates_and_ignores_partial_points() {
+        assert_eq!(world_to_voxel(&[1e20, -1e20, f32::INFINITY], 1.0), vec![i32::MAX, i32::MIN, i32::MAX]);
+        assert_eq!(world_to_voxel(&[-0.1, -0.1, -0.1, 5.0], 1.0), vec![-1, -1, -1]);
+        assert_eq!(voxel_to_world(&[1, 2], 1.0), Vec::<f32>::new());
+    }
+
+    #[test]
+    fn covered_voxels_of_small_negative_and_straddling_boxes() {
+        assert_eq!(AABB::new(0.2, 0.3, 0.4, 0.6, 0.7, 0.8).covered_voxels(1.0), vec![0, 0, 0]);
+        assert_eq!(AABB::new(-0.6, -0.6, -0.6, -0.2, -0.2, -0.2).covered_voxels(1.0), vec![-1, -1, -1]);
+        assert_eq!(AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0).covered_voxels(1.0), vec![0, 0, 0]);
+        assert_eq!(AABB::new(2.0, 2.0, 2.0, 2.0, 2.0, 2.0).covered_voxels(1.0), vec![2, 2, 2]);
+        assert_eq!(
+            AABB::new(-0.5, 0.5, 0.0, 0.5, 1.5, 0.5).covered_voxels(1.0),
+            vec![-1, 0, 0, 0, 0, 0, -1, 1, 0, 0, 1, 0]
+        );
+        assert_eq!(AABB::new(-0.5, 0.0, 0.0, 3.5, 1.0, 1.0).covered_voxels(2.0).len(), 9);
+    }
+
+    #[test]
+    fn covered_voxels_rejects_bad_input() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        assert!(AABB::new(1.0, 0.0, 0.0, 0.0, 1.0, 1.0).covered_voxels(1.0).is_empty());
+        assert!(aabb.covered_voxels(0.0).is_empty());
+        assert!(aabb.covered_voxels(-1.0).is_empty());
+        assert!(aabb.covered_voxels(f32::NAN).is_empty());
+        assert!(aabb.covered_voxels(f32::INFINITY).is_empty());
+        assert!(AABB::new(0.0, 0.0, 0.0, f32::INFINITY, 1.0, 1.0).covered_voxels(1.0).is_empty());
+        assert_eq!(Chunk::new(0.0, 0.0, 0.0).stamp_aabb(&aabb, 1), 1);
+    }
+
+    #[test]
+    fn stamp_aabb_clips_to_the_chunk() {
+        let mut chunk = Chunk::new(-2.0, 0.0, 0.0);
+        assert_eq!(chunk.stamp_aabb(&AABB::new(-3.0, 0.2, 0.2, -0.5, 1.8, 0.8), 4), 4);
+        assert_eq!((chunk.get(0, 0, 0), chunk.get(1, 1, 0), chunk.get(2, 0, 0), chunk.solid_count()), (4, 4, 0, 4));
+        assert!(chunk.is_dirty());
+        assert_eq!(chunk.stamp_aabb(&AABB::new(100.0, 0.0, 0.0, 101.0, 1.0, 1.0), 1), 0);
+
+        let mut full = Chunk::new(0.0, 0.0, 0.0);
+        assert_eq!(full.stamp_aabb(&AABB::new(-1e6, -1e6, -1e6, 1e6, 1e6, 1e6), 1), 32768);
+    }
+
+    #[test]
+    fn covered_voxels_refuses_boxes_over_the_limit() {
+        assert!(AABB::new(-1e6, -1e6, -1e6, 1e6, 1e6, 1e6).covered_voxels(1.0).is_empty());
+        assert!(AABB::new(0.0, 0.0, 0.0, 128.0, 128.0, 65.0).covered_voxels(1.0).is_empty());
+        let at_limit = AABB::new(0.0, 0.0, 0.0, 128.0, 128.0, 64.0).covered_voxels(1.0);
+        assert_eq!(at_limit.len() as u64, 3 * MAX_COVERED_VOXELS);
+    }
+}
@@ -0,0 +1,121 @@
+use wasm_bindgen::prelude::*;
+
+use crate::math;
+use crate::{Ray, VoxelRaycast, AABB};
+
+#[wasm_bindgen]
+impl Ray {
+    /// Distance to the nearest hit on a heightmap of `width * depth` samples
+    /// laid out row by row along x, with sample `(i, j)` at
+    /// `(i * cell_size, heights[i + j * width], j * cell_size)`. Each grid cell
+    /// is split into two triangles, and the surface is hit from above or
+    /// below. Hits beyond `max_distance` are ignored, and a `heights` slice
+    /// that doesn't hold exactly `width * depth` samples never reports a hit.
+    pub fn intersects_heightmap(
+        &self,
+        heights: &[f32],
+        width: u32,
+        depth: u32,
+        cell_size: f32,
+        max_distance: f32,
+    ) -> Option<f32> {
+        if width.checked_mul(depth) != u32::try_from(heights.len()).ok() {
+            return None;
+        }
+        if width < 2 || depth < 2 || !max_distance.is_finite() {
+            return None;
+        }
+
+        let footprint = AABB::new(
+            0.0,
+            f32::NEG_INFINITY,
+            0.0,
+            (width - 1) as f32 * cell_size,
+            f32::INFINITY,
+            (depth - 1) as f32 * cell_size,
+        );
+        let (t_enter, t_exit, _) = self.slab_entry(&footprint)?;
+        let start = t_enter.max(0.0);
+        let limit = max_distance.min(t_exit);
+        if start > limit {
+            return None;
+        }
+
+        let vertex = |i: i32, j: i32| {
+            let height = heights[(i + j * width as i32) as usize];
+            math::point(i as f32 * cell_size, height, j as f32 * cell_size)
+        };
+
+        // March the grid cells under the ray in the XZ plane, measuring `t`
+        // in the ray's own units so cell entry distances compare directly.
+        let entry = self.origin + self.direction * start;
+        let cells = VoxelRaycast::new(
+            math::point(entry.x / cell_size, 0.0, entry.z / cell_size),
+            math::vector(self.direction.x / cell_size, 0.0, self.direction.z / cell_size),
+            limit - start,
+        );
+
+        for step in cells {
+            let (i, j) = (step.cell[0], step.cell[2]);
+            if i < 0 || j < 0 || i >= width as i32 - 1 || j >= depth as i32 - 1 {
+                continue;
+            }
+
+            let (p00, p10, p01, p11) = (vertex(i, j), vertex(i + 1, j), vertex(i, j + 1), vertex(i + 1, j + 1));
+            let hit = [self.triangle_hit(&p00, &p10, &p11, false), self.triangle_hit(&p00, &p11, &p01, false)]
+                .into_iter()
+                .flatten()
+                .map(|(t, _, _)| t)
+                .reduce(f32::min);
+
+            if let Some(t) = hit.filter(|&t| t <= max_distance) {
+                return Some(t);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flat_heightmap_from_above_and_below() {
+        let flat = vec![2.0; 16];
+        let ray = Ray::new(0.5, 10.0, 0.5, 0.2, -1.0, 0.1);
+        let length = (0.04f32 + 1.0 + 0.01).sqrt();
+        let t = ray.intersects_heightmap(&flat, 4, 4, 1.0, 100.0).unwrap();
+        assert!((t - 8.0 * length).abs() < 1e-4, "{t}");
+        assert!(ray.intersects_heightmap(&flat, 4, 4, 1.0, 8.0 * length - 0.01).is_none());
+
+        let below = Ray::new(1.5, -5.0, 1.5, 0.0, 1.0, 0.0);
+        assert!((below.intersects_heightmap(&flat, 4, 4, 1.0, 100.0).unwrap() - 7.0).abs() < 1e-5);
+        let outside = Ray::new(10.0, 10.0, 10.0, 0.0, -1.0, 0.0);
+        assert!(outside.intersects_heightmap(&flat, 4, 4, 1.0, 100.0).is_none());
+    }
+
+    #[test]
+    fn ramp_heightmap_hit_lies_on_the_surface() {
+        // Height is half of x, with 2-unit cells.
+        let ramp: Vec<f32> = (0..25).map(|k| (k % 5) as f32).collect();
+        let level = Ray::new(-10.0, 1.5, 3.0, 1.0, 0.0, 0.0);
+        let t = level.intersects_heightmap(&ramp, 5, 5, 2.0, 100.0).unwrap();
+        assert!((t - 13.0).abs() < 1e-4, "{t}");
+        assert!(Ray::new(-10.0, 10.0, 3.0, 1.0, 0.0, 0.0).intersects_heightmap(&ramp, 5, 5, 2.0, 100.0).is_none());
+
+        let diagonal = Ray::new(0.1, 6.0, 0.3, 0.7, -1.0, 0.9);
+        let t = diagonal.intersects_heightmap(&ramp, 5, 5, 2.0, 100.0).unwrap();
+        let point = diagonal.at(t);
+        assert!((point[1] - point[0] * 0.5).abs() < 1e-3, "{point:?}");
+    }
+
+    #[test]
+    fn mismatched_sample_count_misses() {
+        let ray = Ray::new(0.5, 10.0, 0.5, 0.0, -1.0, 0.0);
+        assert!(ray.intersects_heightmap(&[0.0; 15], 4, 4, 1.0, 100.0).is_none());
+        assert!(ray.intersects_heightmap(&[0.0; 4], u32::MAX, 2, 1.0, 100.0).is_none());
+        assert!(ray.intersects_heightmap(&[0.0; 4], 2, 2, 1.0, 100.0).is_some());
+    }
+}
@@ -1,33 +1,428 @@
+use std::ops::Index;
+
 use wasm_bindgen::prelude::*;
-use nalgebra as na;
+
+use precision::Scalar;
+
+mod batch;
+mod build_info;
+mod bvh;
+mod capsule;
+mod chunk;
+mod compression;
+mod cone;
+mod csg;
+mod cylinder;
+mod flood_fill;
+mod frustum;
+mod grid;
+mod heightmap;
+mod lod;
+mod math;
+mod mesh;
+mod morton;
+mod obb;
+mod octree;
+mod plane;
+mod precision;
+mod region;
+mod sparse_chunk;
+mod spatial_hash;
+mod sphere;
+mod sweep;
+mod triangle;
+mod voxel_raycast;
+mod world;
+
+pub use batch::{raycast_batch, raycast_nearest, raycast_packet};
+pub use build_info::{build_info, BuildInfo};
+pub use bvh::Bvh;
+pub use capsule::Capsule;
+pub use chunk::{Chunk, CHUNK_SIZE};
+pub use cone::Cone;
+pub use cylinder::Cylinder;
+pub use frustum::Frustum;
+pub use grid::{voxel_to_world, world_to_voxel, MAX_COVERED_VOXELS};
+pub use math::{Point3, Vector3};
+pub use mesh::MeshData;
+#[cfg(all(feature = "rayon", not(target_arch = "wasm32")))]
+pub use mesh::mesh_chunks_parallel;
+pub use morton::{morton_decode, morton_encode, MORTON_MAX_COORD};
+pub use obb::Obb;
+pub use octree::Octree;
+pub use plane::Plane;
+pub use precision::{AABBd, Rayd};
+pub use region::ChunkRegion;
+pub use sparse_chunk::SparseChunk;
+pub use spatial_hash::SpatialHashGrid;
+pub use sphere::Sphere;
+pub use sweep::SweepHit;
+pub use voxel_raycast::{VoxelHit, VoxelRaycast, VoxelStep};
+pub use world::{Entity, Position, Velocity, World};
 
 #[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AABB {
-    min: na::Point3<f32>,
-    max: na::Point3<f32>,
+    min: Point3,
+    max: Point3,
 }
 
 #[wasm_bindgen]
 impl AABB {
+    fn clamp_point(&self, point: &Point3) -> Point3 {
+        math::min(math::max(*point, self.min), self.max)
+    }
+
     #[wasm_bindgen(constructor)]
     pub fn new(min_x: f32, min_y: f32, min_z: f32, max_x: f32, max_y: f32, max_z: f32) -> AABB {
         AABB {
-            min: na::Point3::new(min_x, min_y, min_z),
-            max: na::Point3::new(max_x, max_y, max_z),
+            min: math::point(min_x, min_y, min_z),
+            max: math::point(max_x, max_y, max_z),
         }
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn min_x(&self) -> f32 {
+        self.min.x
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn min_y(&self) -> f32 {
+        self.min.y
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn min_z(&self) -> f32 {
+        self.min.z
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn max_x(&self) -> f32 {
+        self.max.x
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn max_y(&self) -> f32 {
+        self.max.y
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn max_z(&self) -> f32 {
+        self.max.z
+    }
+
+    pub fn from_center_extents(cx: f32, cy: f32, cz: f32, hx: f32, hy: f32, hz: f32) -> AABB {
+        AABB {
+            min: math::point(cx - hx, cy - hy, cz - hz),
+            max: math::point(cx + hx, cy + hy, cz + hz),
+        }
+    }
+
+    pub fn center(&self) -> Vec<f32> {
+        let center = math::center(self.min, self.max);
+        vec![center.x, center.y, center.z]
+    }
+
+    pub fn half_extents(&self) -> Vec<f32> {
+        let half = (self.max - self.min) * 0.5;
+        vec![half.x, half.y, half.z]
+    }
+
+    /// Inverted box (min = +inf, max = -inf) that acts as the identity for `union`.
+    pub fn empty() -> AABB {
+        AABB {
+            min: math::point(f32::INFINITY, f32::INFINITY, f32::INFINITY),
+            max: math::point(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+        }
+    }
+
+    /// Whether `min <= max` on every axis.
+    pub fn is_valid(&self) -> bool {
+        self.min.x <= self.max.x && self.min.y <= self.max.y && self.min.z <= self.max.z
+    }
+
+    /// Volume of the box, or 0 for an invalid box.
+    pub fn volume(&self) -> f32 {
+        if !self.is_valid() {
+            return 0.0;
+        }
+        let size = self.max - self.min;
+        size.x * size.y * size.z
+    }
+
+    /// Surface area of the box, or 0 for an invalid box.
+    pub fn surface_area(&self) -> f32 {
+        if !self.is_valid() {
+            return 0.0;
+        }
+        let size = self.max - self.min;
+        2.0 * (size.x * size.y + size.y * size.z + size.x * size.z)
+    }
+
+    /// Whether every coordinate of `min` and `max` is within `epsilon` of
+    /// `other`'s.
+    pub fn approx_eq(&self, other: &AABB, epsilon: f32) -> bool {
+        approx_eq(&self.min, &other.min, epsilon) && approx_eq(&self.max, &other.max, epsilon)
+    }
+
     pub fn intersects(&self, other: &AABB) -> bool {
         self.min.x <= other.max.x && self.max.x >= other.min.x &&
         self.min.y <= other.max.y && self.max.y >= other.min.y &&
         self.min.z <= other.max.z && self.max.z >= other.min.z
     }
+
+    pub fn contains_point(&self, x: f32, y: f32, z: f32) -> bool {
+        x >= self.min.x && x <= self.max.x &&
+        y >= self.min.y && y <= self.max.y &&
+        z >= self.min.z && z <= self.max.z
+    }
+
+    /// Tests points given as `x, y, z` per point with the same inclusive rule
+    /// as `contains_point`, returning 1 for each point inside and 0 otherwise.
+    pub fn contains_points(&self, points_flat: &[f32]) -> Vec<u8> {
+        points_flat
+            .chunks_exact(3)
+            .map(|p| self.contains_point(p[0], p[1], p[2]) as u8)
+            .collect()
+    }
+
+    pub fn contains_aabb(&self, other: &AABB) -> bool {
+        other.min.x >= self.min.x && other.max.x <= self.max.x &&
+        other.min.y >= self.min.y && other.max.y <= self.max.y &&
+        other.min.z >= self.min.z && other.max.z <= self.max.z
+    }
+
+    pub fn union(&self, other: &AABB) -> AABB {
+        AABB {
+            min: math::min(self.min, other.min),
+            max: math::max(self.max, other.max),
+        }
+    }
+
+    /// Box grown outward by `margin` on every face; a negative margin shrinks
+    /// it and can leave it invalid.
+    pub fn expand(&self, margin: f32) -> AABB {
+        let margin = math::splat(margin);
+        AABB {
+            min: self.min - margin,
+            max: self.max + margin,
+        }
+    }
+
+    /// Overlap of the two boxes, or `None` when they are disjoint or only touch.
+    pub fn intersection(&self, other: &AABB) -> Option<AABB> {
+        let overlap = AABB {
+            min: math::max(self.min, other.min),
+            max: math::min(self.max, other.max),
+        };
+        let touching = (0..3).any(|axis| overlap.min[axis] == overlap.max[axis]);
+        if overlap.is_valid() && !touching {
+            Some(overlap)
+        } else {
+            None
+        }
+    }
+
+    /// Smallest translation `[x, y, z]` that moves this box out of `other`,
+    /// along the axis of least overlap, or `None` when they don't overlap
+    /// (touching boxes need no push). Ties go to the lowest axis, and a box
+    /// centered on `other` along that axis is pushed towards +.
+    pub fn penetration(&self, other: &AABB) -> Option<Vec<f32>> {
+        let mut best: Option<(f32, usize)> = None;
+        for axis in 0..3 {
+            let forward = other.max[axis] - self.min[axis];
+            let backward = self.max[axis] - other.min[axis];
+            if forward <= 0.0 || backward <= 0.0 {
+                return None;
+            }
+
+            let push = if forward <= backward { forward } else { -backward };
+            if best.is_none_or(|(depth, _)| push.abs() < depth.abs()) {
+                best = Some((push, axis));
+            }
+        }
+
+        let (push, axis) = best?;
+        let mut mtv = vec![0.0; 3];
+        mtv[axis] = push;
+        Some(mtv)
+    }
+
+    /// Per-axis overlap `[x, y, z]` with `other`; a negative value is the gap
+    /// between the boxes on that axis.
+    pub fn overlap_extents(&self, other: &AABB) -> Vec<f32> {
+        let overlap = math::min(self.max, other.max) - math::max(self.min, other.min);
+        vec![overlap.x, overlap.y, overlap.z]
+    }
+
+    /// Shortest distance between the two boxes, 0 when they touch or overlap.
+    pub fn distance(&self, other: &AABB) -> f32 {
+        let overlap = math::min(self.max, other.max) - math::max(self.min, other.min);
+        let gap = math::vector(overlap.x.min(0.0), overlap.y.min(0.0), overlap.z.min(0.0));
+        math::length(gap)
+    }
+
+    /// Componentwise blend of `min` and `max` towards `other`, with `t`
+    /// clamped to `[0, 1]`.
+    pub fn lerp(&self, other: &AABB, t: f32) -> AABB {
+        let t = t.clamp(0.0, 1.0);
+        AABB {
+            min: math::lerp(self.min, other.min, t),
+            max: math::lerp(self.max, other.max, t),
+        }
+    }
+
+    pub fn merge(&mut self, other: &AABB) {
+        self.min = math::min(self.min, other.min);
+        self.max = math::max(self.max, other.max);
+    }
+
+    pub fn expand_to_include(&mut self, x: f32, y: f32, z: f32) {
+        let point = math::point(x, y, z);
+        self.min = math::min(self.min, point);
+        self.max = math::max(self.max, point);
+    }
+
+    /// Closest point on or inside the box to the query point.
+    pub fn closest_point(&self, x: f32, y: f32, z: f32) -> Vec<f32> {
+        let closest = self.clamp_point(&math::point(x, y, z));
+        vec![closest.x, closest.y, closest.z]
+    }
+
+    /// Euclidean distance from the query point to the box, 0 when inside.
+    pub fn distance_to_point(&self, x: f32, y: f32, z: f32) -> f32 {
+        let point = math::point(x, y, z);
+        math::distance(point, self.clamp_point(&point))
+    }
+
+    /// Whether the segment from `(x0, y0, z0)` to `(x1, y1, z1)` touches the box.
+    pub fn intersects_segment(&self, x0: f32, y0: f32, z0: f32, x1: f32, y1: f32, z1: f32) -> bool {
+        self.segment_interval(x0, y0, z0, x1, y1, z1).is_some()
+    }
+
+    /// JS form of [`AABB::segment_interval`], returning `[t_enter, t_exit]`.
+    #[wasm_bindgen(js_name = segment_interval)]
+    pub fn segment_interval_js(&self, x0: f32, y0: f32, z0: f32, x1: f32, y1: f32, z1: f32) -> Option<Vec<f32>> {
+        self.segment_interval(x0, y0, z0, x1, y1, z1).map(|(enter, exit)| vec![enter, exit])
+    }
+
+    /// The eight corners as 24 floats, `x, y, z` per corner. Corner `i` takes
+    /// the max x when bit 0 of `i` is set, max y for bit 1 and max z for bit 2,
+    /// so corner 0 is `min` and corner 7 is `max`.
+    pub fn corners(&self) -> Vec<f32> {
+        self.corner_points().iter().flat_map(|corner| [corner.x, corner.y, corner.z]).collect()
+    }
+
+    /// The eight boxes made by splitting at `center()`. Octant `i` is the one
+    /// touching corner `i` of `corners()`.
+    pub fn octants(&self) -> Vec<AABB> {
+        self.octant_boxes().to_vec()
+    }
+
+    /// Box enclosing this one after transforming its eight corners by a 4x4
+    /// matrix given as 16 floats in column-major order, or `None` when
+    /// `matrix` doesn't hold exactly 16 floats.
+    pub fn transform(&self, matrix: &[f32]) -> Option<AABB> {
+        if matrix.len() != 16 {
+            return None;
+        }
+        let matrix = math::matrix4(matrix);
+
+        let mut corners = self.corner_points().into_iter().map(|corner| math::transform_point(&matrix, corner));
+        let first = corners.next().unwrap();
+        Some(corners.fold(AABB { min: first, max: first }, |aabb, corner| AABB {
+            min: math::min(aabb.min, corner),
+            max: math::max(aabb.max, corner),
+        }))
+    }
+}
+
+impl AABB {
+    #[cfg(feature = "glam")]
+    pub fn from_glam(min: glam::Vec3, max: glam::Vec3) -> AABB {
+        AABB { min, max }
+    }
+
+    /// `(min, max)` corners as glam vectors.
+    #[cfg(feature = "glam")]
+    pub fn to_glam(&self) -> (glam::Vec3, glam::Vec3) {
+        (self.min, self.max)
+    }
+
+    /// Portion of the segment from `(x0, y0, z0)` to `(x1, y1, z1)` inside the
+    /// box, as entry and exit fractions in `[0, 1]`. A segment starting inside
+    /// enters at 0.
+    pub fn segment_interval(&self, x0: f32, y0: f32, z0: f32, x1: f32, y1: f32, z1: f32) -> Option<(f32, f32)> {
+        let start = math::point(x0, y0, z0);
+        let delta = math::point(x1, y1, z1) - start;
+        let (tmin, tmax, _) = slab(&start, &delta, &self.min, &self.max)?;
+
+        let enter = tmin.max(0.0);
+        let exit = tmax.min(1.0);
+        if enter <= exit {
+            Some((enter, exit))
+        } else {
+            None
+        }
+    }
+
+    // Corner `i` takes max x when bit 0 is set, max y for bit 1 and max z for bit 2.
+    fn corner_points(&self) -> [Point3; 8] {
+        std::array::from_fn(|i| {
+            math::point(
+                if i & 1 == 0 { self.min.x } else { self.max.x },
+                if i & 2 == 0 { self.min.y } else { self.max.y },
+                if i & 4 == 0 { self.min.z } else { self.max.z },
+            )
+        })
+    }
+
+    pub(crate) fn octant_boxes(&self) -> [AABB; 8] {
+        let center = math::center(self.min, self.max);
+        self.corner_points().map(|corner| AABB {
+            min: math::min(corner, center),
+            max: math::max(corner, center),
+        })
+    }
 }
 
 #[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ray {
-    origin: na::Point3<f32>,
-    direction: na::Vector3<f32>,
+    origin: Point3,
+    direction: Vector3,
+}
+
+/// Where a ray enters a box, from [`Ray::hit_aabb`].
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct RayHit {
+    t: f32,
+    point: Point3,
+    normal: Vector3,
+}
+
+#[wasm_bindgen]
+impl RayHit {
+    /// Distance along the ray to the hit.
+    #[wasm_bindgen(getter)]
+    pub fn t(&self) -> f32 {
+        self.t
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn point(&self) -> Vec<f32> {
+        vec![self.point.x, self.point.y, self.point.z]
+    }
+
+    /// Outward normal of the face the ray entered through, or zero when the
+    /// ray started inside the box.
+    #[wasm_bindgen(getter)]
+    pub fn normal(&self) -> Vec<f32> {
+        vec![self.normal.x, self.normal.y, self.normal.z]
+    }
 }
 
 #[wasm_bindgen]
@@ -35,28 +430,625 @@ impl Ray {
     #[wasm_bindgen(constructor)]
     pub fn new(origin_x: f32, origin_y: f32, origin_z: f32, dir_x: f32, dir_y: f32, dir_z: f32) -> Ray {
         Ray {
-            origin: na::Point3::new(origin_x, origin_y, origin_z),
-            direction: na::Vector3::new(dir_x, dir_y, dir_z).normalize(),
+            origin: math::point(origin_x, origin_y, origin_z),
+            direction: math::vector(dir_x, dir_y, dir_z).normalize(),
         }
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn origin(&self) -> Vec<f32> {
+        vec![self.origin.x, self.origin.y, self.origin.z]
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn direction(&self) -> Vec<f32> {
+        vec![self.direction.x, self.direction.y, self.direction.z]
+    }
+
+    /// Point at distance `t` along the ray. The direction is normalized on
+    /// construction, so `t` is measured in world units.
+    pub fn at(&self, t: f32) -> Vec<f32> {
+        let point = self.origin + self.direction * t;
+        vec![point.x, point.y, point.z]
+    }
+
+    /// Whether the origins and the (normalized) directions match to within
+    /// `epsilon` per component.
+    pub fn approx_eq(&self, other: &Ray, epsilon: f32) -> bool {
+        approx_eq(&self.origin, &other.origin, epsilon) && approx_eq(&self.direction, &other.direction, epsilon)
+    }
+
+    /// Ray from the same origin with the direction mirrored about `normal`.
+    pub fn reflect(&self, normal: &[f32]) -> Ray {
+        let n = math::vector(normal[0], normal[1], normal[2]).normalize();
+        let d = self.direction - n * (2.0 * math::dot(self.direction, n));
+        Ray { origin: self.origin, direction: d.normalize() }
+    }
+
+    /// Ray from the same origin bent through a surface with `normal`, where
+    /// `eta` is the ratio of refractive indices (incident over transmitted).
+    /// The normal may face either way. Returns `None` on total internal
+    /// reflection.
+    pub fn refract(&self, normal: &[f32], eta: f32) -> Option<Ray> {
+        let mut n = math::vector(normal[0], normal[1], normal[2]).normalize();
+        let mut cos_i = -math::dot(self.direction, n);
+        if cos_i < 0.0 {
+            n = -n;
+            cos_i = -cos_i;
+        }
+
+        let k = 1.0 - eta * eta * (1.0 - cos_i * cos_i);
+        if k < 0.0 {
+            return None;
+        }
+
+        let d = self.direction * eta + n * (eta * cos_i - k.sqrt());
+        Some(Ray { origin: self.origin, direction: d.normalize() })
+    }
+
+    /// Whether the ray hits `aabb` at any distance ahead of the origin. A ray
+    /// starting inside the box always hits it.
     pub fn intersects_aabb(&self, aabb: &AABB) -> bool {
-        let inv_dir = na::Vector3::new(
-            1.0 / self.direction.x,
-            1.0 / self.direction.y,
-            1.0 / self.direction.z,
-        );
+        self.slab_interval(aabb).is_some()
+    }
 
-        let t1 = ((if inv_dir.x >= 0.0 { aabb.min.x } else { aabb.max.x }) - self.origin.x) * inv_dir.x;
-        let t2 = ((if inv_dir.x >= 0.0 { aabb.max.x } else { aabb.min.x }) - self.origin.x) * inv_dir.x;
-        let t3 = ((if inv_dir.y >= 0.0 { aabb.min.y } else { aabb.max.y }) - self.origin.y) * inv_dir.y;
-        let t4 = ((if inv_dir.y >= 0.0 { aabb.max.y } else { aabb.min.y }) - self.origin.y) * inv_dir.y;
-        let t5 = ((if inv_dir.z >= 0.0 { aabb.min.z } else { aabb.max.z }) - self.origin.z) * inv_dir.z;
-        let t6 = ((if inv_dir.z >= 0.0 { aabb.max.z } else { aabb.min.z }) - self.origin.z) * inv_dir.z;
+    /// Like `intersects_aabb`, but only for boxes entered within
+    /// `max_distance`. A ray starting inside the box hits it for any
+    /// non-negative `max_distance`.
+    pub fn intersects_aabb_within(&self, aabb: &AABB, max_distance: f32) -> bool {
+        self.distance_to_aabb(aabb).is_some_and(|t| t <= max_distance)
+    }
 
-        let tmin = t1.max(t3).max(t5);
-        let tmax = t2.min(t4).min(t6);
+    /// Distance along the ray to the entry point of `aabb`, or `None` on a miss.
+    /// A ray starting inside the box reports `0.0`.
+    pub fn distance_to_aabb(&self, aabb: &AABB) -> Option<f32> {
+        self.slab_interval(aabb).map(|(tmin, _)| tmin.max(0.0))
+    }
 
-        tmax >= tmin && tmax >= 0.0
+    /// Entry distance, point and face normal in one call, combining
+    /// `distance_to_aabb`, `at` and `intersect_aabb_face`.
+    pub fn hit_aabb(&self, aabb: &AABB) -> Option<RayHit> {
+        let (t, face) = self.intersect_aabb_face(aabb)?;
+        let mut normal = math::zero();
+        if face >= 0 {
+            normal[face as usize / 2] = if face % 2 == 0 { -1.0 } else { 1.0 };
+        }
+        Some(RayHit { t, point: self.origin + self.direction * t, normal })
     }
-} 
\ No newline at end of file
+
+    /// Points `x, y, z` spaced `step` apart along the part of the ray inside
+    /// `aabb`, starting at the entry point and stopping at the exit or after
+    /// `max_samples` points. A box thinner than one step, or a step that
+    /// isn't positive, yields just the entry point; a miss yields nothing.
+    pub fn march_aabb(&self, aabb: &AABB, step: f32, max_samples: u32) -> Vec<f32> {
+        let Some((enter, exit)) = self.aabb_interval(aabb) else {
+            return Vec::new();
+        };
+
+        let count = if step > 0.0 { ((exit - enter) / step).floor() as u32 + 1 } else { 1 };
+        (0..count.min(max_samples))
+            .map(|k| self.origin + self.direction * (enter + step * k as f32))
+            .flat_map(|point| [point.x, point.y, point.z])
+            .collect()
+    }
+
+    /// JS form of [`Ray::aabb_interval`], returning `[t_enter, t_exit]`.
+    #[wasm_bindgen(js_name = aabb_interval)]
+    pub fn aabb_interval_js(&self, aabb: &AABB) -> Option<Vec<f32>> {
+        self.aabb_interval(aabb).map(|(enter, exit)| vec![enter, exit])
+    }
+
+    /// JS form of [`Ray::intersect_aabb_face`], returning `[t, face]`.
+    #[wasm_bindgen(js_name = intersect_aabb_face)]
+    pub fn intersect_aabb_face_js(&self, aabb: &AABB) -> Option<Vec<f32>> {
+        self.intersect_aabb_face(aabb).map(|(t, face)| vec![t, face as f32])
+    }
+}
+
+impl Ray {
+    /// Distances at which the ray enters and leaves `aabb`. The entry is
+    /// clamped to 0 when the origin is inside; a ray grazing a face or edge
+    /// reports the touching span.
+    pub fn aabb_interval(&self, aabb: &AABB) -> Option<(f32, f32)> {
+        self.slab_interval(aabb).map(|(tmin, tmax)| (tmin.max(0.0), tmax))
+    }
+
+    /// Entry distance and the face of `aabb` the ray enters through, numbered
+    /// 0..5 for -X, +X, -Y, +Y, -Z, +Z. Corner and edge hits resolve to the
+    /// lowest axis. A ray starting inside the box reports `(0.0, -1)`.
+    pub fn intersect_aabb_face(&self, aabb: &AABB) -> Option<(f32, i32)> {
+        let (tmin, _, axis) = self.slab_entry(aabb)?;
+        Some(entry_face(tmin, axis, &self.direction))
+    }
+
+    fn slab_interval(&self, aabb: &AABB) -> Option<(f32, f32)> {
+        self.slab_entry(aabb).map(|(tmin, tmax, _)| (tmin, tmax))
+    }
+
+    pub(crate) fn slab_entry(&self, aabb: &AABB) -> Option<(f32, f32, usize)> {
+        ray_slab(&self.origin, &self.direction, &aabb.min, &aabb.max)
+    }
+}
+
+fn approx_eq(a: &impl Index<usize, Output = f32>, b: &impl Index<usize, Output = f32>, epsilon: f32) -> bool {
+    (0..3).all(|axis| (a[axis] - b[axis]).abs() <= epsilon)
+}
+
+fn point3(values: &[f32]) -> Point3 {
+    math::point(values[0], values[1], values[2])
+}
+
+// Slab test for `origin + direction * t` against the box `min..max`, returning
+// the raw `(tmin, tmax)` interval and the axis whose slab produced `tmin`. Axes
+// the direction is parallel to are checked by position instead of dividing by
+// zero, so `0 * inf` never produces NaN. Generic so `Rayd` shares it.
+fn slab<T: Scalar>(
+    origin: &impl Index<usize, Output = T>,
+    direction: &impl Index<usize, Output = T>,
+    min: &impl Index<usize, Output = T>,
+    max: &impl Index<usize, Output = T>,
+) -> Option<(T, T, usize)> {
+    let mut tmin = -T::INFINITY;
+    let mut tmax = T::INFINITY;
+    let mut entry_axis = 0;
+
+    for axis in 0..3 {
+        if direction[axis] == T::zero() {
+            if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                return None;
+            }
+            continue;
+        }
+
+        let inv_dir = T::one() / direction[axis];
+        let t1 = (min[axis] - origin[axis]) * inv_dir;
+        let t2 = (max[axis] - origin[axis]) * inv_dir;
+
+        if t1.min(t2) > tmin {
+            tmin = t1.min(t2);
+            entry_axis = axis;
+        }
+        tmax = tmax.min(t1.max(t2));
+    }
+
+    Some((tmin, tmax, entry_axis))
+} 
+
+// `slab` for a ray rather than a line: `None` unless part of the box lies
+// ahead of the origin. Shared by `Ray` and `Rayd`.
+fn ray_slab<T: Scalar>(
+    origin: &impl Index<usize, Output = T>,
+    direction: &impl Index<usize, Output = T>,
+    min: &impl Index<usize, Output = T>,
+    max: &impl Index<usize, Output = T>,
+) -> Option<(T, T, usize)> {
+    let (tmin, tmax, axis) = slab(origin, direction, min, max)?;
+
+    if tmax >= tmin && tmax >= T::zero() {
+        Some((tmin, tmax, axis))
+    } else {
+        None
+    }
+}
+
+// Entry distance and face for a `ray_slab` hit, numbered as in
+// `Ray::intersect_aabb_face`.
+fn entry_face<T: Scalar>(tmin: T, axis: usize, direction: &impl Index<usize, Output = T>) -> (T, i32) {
+    if tmin < T::zero() {
+        return (T::zero(), -1);
+    }
+
+    let face = axis as i32 * 2 + if direction[axis] > T::zero() { 0 } else { 1 };
+    (tmin, face)
+}
+
+// Seeded generators shared by the randomized tests.
+#[cfg(test)]
+pub(crate) mod test_util {
+    use crate::AABB;
+
+    // Fixed-seed 64-bit LCG yielding the top 32 bits of each state.
+    pub(crate) fn lcg(seed: u64) -> impl FnMut() -> u32 {
+        let mut state = seed;
+        move || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 32) as u32
+        }
+    }
+
+    // `lcg` scaled to floats in `[0, 1)`.
+    pub(crate) fn lcg_unit(seed: u64) -> impl FnMut() -> f32 {
+        let mut next = lcg(seed);
+        move || (next() >> 8) as f32 / (1 << 24) as f32
+    }
+
+    // Boxes with corners in `-span / 2..span / 2` and edges up to `size`.
+    pub(crate) fn random_boxes(seed: u64, count: usize, span: f32, size: f32) -> Vec<AABB> {
+        let mut next = lcg_unit(seed);
+        (0..count)
+            .map(|_| {
+                let min = [next(), next(), next()].map(|c| c * span - span / 2.0);
+                let extent = [next(), next(), next()].map(|c| c * size);
+                AABB::new(min[0], min[1], min[2], min[0] + extent[0], min[1] + extent[1], min[2] + extent[2])
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn axis_aligned_ray_grazing_a_face_hits() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        assert_eq!(Ray::new(0.0, 5.0, 0.5, 0.0, -1.0, 0.0).distance_to_aabb(&aabb), Some(4.0));
+        assert_eq!(Ray::new(1.0, 5.0, 1.0, 0.0, -1.0, 0.0).distance_to_aabb(&aabb), Some(4.0));
+        assert_eq!(Ray::new(1.01, 5.0, 0.5, 0.0, -1.0, 0.0).distance_to_aabb(&aabb), None);
+    }
+
+    #[test]
+    fn ray_starting_on_a_face_plane_hits_at_zero() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        assert_eq!(Ray::new(0.0, 0.5, 0.5, 1.0, 0.0, 0.0).distance_to_aabb(&aabb), Some(0.0));
+        assert_eq!(Ray::new(0.0, 0.5, 0.5, -1.0, 0.0, 0.0).distance_to_aabb(&aabb), Some(0.0));
+        assert_eq!(Ray::new(0.5, -0.0, 0.5, -0.0, 1.0, -0.0).distance_to_aabb(&aabb), Some(0.0));
+    }
+
+    #[test]
+    fn straight_down_camera_ray_picks_the_ground() {
+        let ground = AABB::new(-10.0, -1.0, -10.0, 10.0, 0.0, 10.0);
+        let ray = Ray::new(0.0, 20.0, 0.0, 0.0, -1.0, 0.0);
+        assert!(ray.intersects_aabb(&ground));
+        assert_eq!(ray.distance_to_aabb(&ground), Some(20.0));
+        assert_eq!(ray.intersect_aabb_face(&ground), Some((20.0, 3)));
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn glam_conversions_round_trip() {
+        let aabb = AABB::from_glam(glam::Vec3::new(0.0, 1.0, 2.0), glam::Vec3::new(3.0, 4.0, 5.0));
+        assert_eq!(aabb.to_glam(), (glam::Vec3::new(0.0, 1.0, 2.0), glam::Vec3::new(3.0, 4.0, 5.0)));
+        assert_eq!(aabb.max_y(), 4.0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_uses_plain_arrays() {
+        let aabb = AABB::new(0.0, 1.0, 2.0, 3.0, 4.0, 5.0);
+        let json = serde_json::to_string(&aabb).unwrap();
+        assert_eq!(json, r#"{"min":[0.0,1.0,2.0],"max":[3.0,4.0,5.0]}"#);
+        assert_eq!(serde_json::from_str::<AABB>(&json).unwrap(), aabb);
+
+        let ray = Ray::new(1.0, 2.0, 3.0, 0.0, 0.0, 2.0);
+        let json = serde_json::to_string(&ray).unwrap();
+        assert_eq!(json, r#"{"origin":[1.0,2.0,3.0],"direction":[0.0,0.0,1.0]}"#);
+        assert_eq!(serde_json::from_str::<Ray>(&json).unwrap(), ray);
+    }
+
+    #[test]
+    fn intersect_aabb_face_reports_each_face() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        let cases = [
+            ([-1.0, 0.5, 0.5], [1.0, 0.0, 0.0], 0),
+            ([2.0, 0.5, 0.5], [-1.0, 0.0, 0.0], 1),
+            ([0.5, -1.0, 0.5], [0.0, 1.0, 0.0], 2),
+            ([0.5, 2.0, 0.5], [0.0, -1.0, 0.0], 3),
+            ([0.5, 0.5, -1.0], [0.0, 0.0, 1.0], 4),
+            ([0.5, 0.5, 2.0], [0.0, 0.0, -1.0], 5),
+        ];
+        for (o, d, face) in cases {
+            let ray = Ray::new(o[0], o[1], o[2], d[0], d[1], d[2]);
+            assert_eq!(ray.intersect_aabb_face(&aabb), Some((1.0, face)));
+            assert_eq!(ray.intersect_aabb_face_js(&aabb), Some(vec![1.0, face as f32]));
+        }
+        assert_eq!(Ray::new(0.5, 0.5, 0.5, 1.0, 1.0, 0.0).intersect_aabb_face(&aabb), Some((0.0, -1)));
+        assert_eq!(Ray::new(-1.0, 5.0, 0.5, 1.0, 0.0, 0.0).intersect_aabb_face(&aabb), None);
+    }
+
+    #[test]
+    fn intersect_aabb_face_breaks_corner_ties_toward_the_lowest_axis() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        assert_eq!(Ray::new(-1.0, -1.0, 0.5, 1.0, 1.0, 0.0).intersect_aabb_face(&aabb).unwrap().1, 0);
+        assert_eq!(Ray::new(0.5, 2.0, 2.0, 0.0, -1.0, -1.0).intersect_aabb_face(&aabb).unwrap().1, 3);
+        assert_eq!(Ray::new(2.0, 2.0, 2.0, -1.0, -1.0, -1.0).intersect_aabb_face(&aabb).unwrap().1, 1);
+    }
+
+    #[test]
+    fn transform_bounds_a_rotated_box_tightly() {
+        let (s, c) = std::f32::consts::FRAC_PI_4.sin_cos();
+        // Rotation by 45 degrees about z, then a translation, column-major.
+        let matrix = [c, s, 0.0, 0.0, -s, c, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 10.0, 0.0, 0.0, 1.0];
+        let aabb = AABB::new(-1.0, -1.0, -1.0, 1.0, 1.0, 1.0).transform(&matrix).unwrap();
+
+        let reach = std::f32::consts::SQRT_2;
+        let expected = AABB::new(10.0 - reach, -reach, -1.0, 10.0 + reach, reach, 1.0);
+        assert!(aabb.approx_eq(&expected, 1e-5), "{:?}", aabb.corners());
+
+        // Scaling the half extents by their length would give sqrt(3) on
+        // every axis.
+        let loose = 3.0f32.sqrt();
+        assert!(aabb.max_x() - 10.0 < loose - 0.3);
+        assert!(aabb.max_z() < loose - 0.7);
+    }
+
+    #[test]
+    fn transform_rejects_a_matrix_of_the_wrong_size() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 2.0, 3.0);
+        assert_eq!(aabb.transform(&[1.0; 12]), None);
+        assert_eq!(aabb.transform(&[1.0; 17]), None);
+        assert_eq!(aabb.transform(&[]), None);
+        let scale = [2.0, 0.0, 0.0, 0.0, 0.0, 3.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0];
+        assert_eq!(aabb.transform(&scale), Some(AABB::new(0.0, 0.0, 0.0, 2.0, 6.0, 3.0)));
+    }
+
+    #[test]
+    fn closest_point_inside_on_face_and_at_a_corner() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        assert_eq!(aabb.closest_point(0.5, 0.5, 0.5), vec![0.5, 0.5, 0.5]);
+        assert_eq!(aabb.distance_to_point(0.5, 0.5, 0.5), 0.0);
+        assert_eq!(aabb.closest_point(1.0, 0.5, 0.5), vec![1.0, 0.5, 0.5]);
+        assert_eq!(aabb.distance_to_point(1.0, 0.5, 0.5), 0.0);
+
+        assert_eq!(aabb.closest_point(0.5, 3.0, 0.5), vec![0.5, 1.0, 0.5]);
+        assert_eq!(aabb.distance_to_point(0.5, 3.0, 0.5), 2.0);
+        assert_eq!(aabb.closest_point(2.0, 2.0, -1.0), vec![1.0, 1.0, 0.0]);
+        assert!((aabb.distance_to_point(2.0, 2.0, -1.0) - 3.0f32.sqrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn empty_is_the_identity_for_union() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 2.0, 3.0);
+        assert_eq!(aabb.volume(), 6.0);
+        assert_eq!(aabb.surface_area(), 22.0);
+        assert!(aabb.is_valid());
+
+        let empty = AABB::empty();
+        assert!(!empty.is_valid());
+        assert_eq!(empty.volume(), 0.0);
+        assert_eq!(empty.surface_area(), 0.0);
+        assert_eq!(empty.union(&aabb), aabb);
+        assert_eq!(aabb.union(&empty), aabb);
+
+        let mut point = AABB::empty();
+        point.expand_to_include(1.0, 2.0, 3.0);
+        assert!(point.is_valid());
+        assert_eq!(point.volume(), 0.0);
+    }
+
+    #[test]
+    fn contains_points_includes_the_boundary() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        let points = [0.5, 0.5, 0.5, 1.0, 0.0, 1.0, 1.01, 0.5, 0.5, -0.1, 0.0, 0.0];
+        assert_eq!(aabb.contains_points(&points), vec![1, 1, 0, 0]);
+        // A trailing partial point is ignored.
+        assert_eq!(aabb.contains_points(&points[..5]), vec![1]);
+        assert!(aabb.contains_points(&[]).is_empty());
+    }
+
+    #[test]
+    fn reflect_mirrors_a_45_degree_ray() {
+        let ray = Ray::new(0.0, 1.0, 0.0, 1.0, -1.0, 0.0);
+        let reflected = ray.reflect(&[0.0, 2.0, 0.0]);
+        let h = std::f32::consts::FRAC_1_SQRT_2;
+        assert!(reflected.approx_eq(&Ray::new(0.0, 1.0, 0.0, h, h, 0.0), 1e-6));
+        // Which way the normal faces makes no difference.
+        assert!(ray.reflect(&[0.0, -1.0, 0.0]).approx_eq(&reflected, 1e-6));
+    }
+
+    #[test]
+    fn refract_follows_snell_and_reflects_totally_when_grazing() {
+        let down = Ray::new(0.0, 0.0, 0.0, 0.0, -1.0, 0.0);
+        assert!(down.refract(&[0.0, 1.0, 0.0], 1.5).unwrap().approx_eq(&down, 1e-6));
+
+        // 30 degrees from the normal going from air into glass.
+        let (sin_i, cos_i) = std::f32::consts::FRAC_PI_6.sin_cos();
+        let ray = Ray::new(0.0, 0.0, 0.0, sin_i, -cos_i, 0.0);
+        let refracted = ray.refract(&[0.0, 1.0, 0.0], 1.0 / 1.5).unwrap();
+        let direction = refracted.direction();
+        assert!((direction[0] - sin_i / 1.5).abs() < 1e-6);
+        assert!(direction[1] < 0.0);
+        assert!(ray.refract(&[0.0, -1.0, 0.0], 1.0 / 1.5).unwrap().approx_eq(&refracted, 1e-6));
+
+        // Leaving glass at 80 degrees is past the critical angle of ~41.8.
+        let (sin_i, cos_i) = 80.0f32.to_radians().sin_cos();
+        assert_eq!(Ray::new(0.0, 0.0, 0.0, sin_i, -cos_i, 0.0).refract(&[0.0, 1.0, 0.0], 1.5), None);
+    }
+
+    #[test]
+    fn segments_hit_only_within_their_length() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        // Pointing at the box but stopping short; the line would hit.
+        assert!(!aabb.intersects_segment(-3.0, 0.5, 0.5, -1.0, 0.5, 0.5));
+        assert!(Ray::new(-3.0, 0.5, 0.5, 1.0, 0.0, 0.0).intersects_aabb(&aabb));
+        // Reaching exactly to the face, and passing through.
+        assert!(aabb.intersects_segment(-1.0, 0.5, 0.5, 0.0, 0.5, 0.5));
+        assert_eq!(aabb.segment_interval(-1.0, 0.5, 0.5, 3.0, 0.5, 0.5), Some((0.25, 0.5)));
+        assert_eq!(aabb.segment_interval_js(-1.0, 0.5, 0.5, 3.0, 0.5, 0.5), Some(vec![0.25, 0.5]));
+        // Wholly inside, and degenerate segments inside and out.
+        assert_eq!(aabb.segment_interval(0.5, 0.5, 0.5, 0.5, 0.5, 0.6), Some((0.0, 1.0)));
+        assert!(aabb.intersects_segment(0.2, 0.2, 0.2, 0.2, 0.2, 0.2));
+        assert!(!aabb.intersects_segment(2.0, 2.0, 2.0, 2.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn expand_grows_and_shrinks_every_face() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 2.0, 2.0, 2.0);
+        assert_eq!(aabb.expand(1.0), AABB::new(-1.0, -1.0, -1.0, 3.0, 3.0, 3.0));
+        assert_eq!(aabb.expand(-0.5), AABB::new(0.5, 0.5, 0.5, 1.5, 1.5, 1.5));
+
+        let inverted = aabb.expand(-1.5);
+        assert!(!inverted.is_valid());
+        assert_eq!(inverted.volume(), 0.0);
+        assert_eq!(inverted.intersection(&aabb), None);
+    }
+
+    #[test]
+    fn intersection_requires_volume() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 2.0, 2.0, 2.0);
+        let overlap = AABB::new(1.0, 1.0, 1.0, 5.0, 5.0, 5.0);
+        assert_eq!(aabb.intersection(&overlap), Some(AABB::new(1.0, 1.0, 1.0, 2.0, 2.0, 2.0)));
+        assert_eq!(overlap.intersection(&aabb), aabb.intersection(&overlap));
+        assert_eq!(aabb.intersection(&aabb), Some(aabb));
+        assert_eq!(aabb.intersection(&AABB::new(2.0, 0.0, 0.0, 3.0, 2.0, 2.0)), None);
+        assert_eq!(aabb.intersection(&AABB::new(3.0, 0.0, 0.0, 4.0, 2.0, 2.0)), None);
+    }
+
+    #[test]
+    fn octants_tile_the_box_without_overlap() {
+        let aabb = AABB::new(-1.0, 0.0, 2.0, 3.0, 4.0, 10.0);
+        let corners = aabb.corners();
+        assert_eq!(corners.len(), 24);
+        assert_eq!(&corners[..3], &[-1.0, 0.0, 2.0]);
+        assert_eq!(&corners[3..6], &[3.0, 0.0, 2.0]);
+        assert_eq!(&corners[21..], &[3.0, 4.0, 10.0]);
+
+        let octants = aabb.octants();
+        let mut union = AABB::empty();
+        let mut volume = 0.0;
+        for (i, octant) in octants.iter().enumerate() {
+            union.merge(octant);
+            volume += octant.volume();
+            assert!(octant.contains_point(corners[i * 3], corners[i * 3 + 1], corners[i * 3 + 2]));
+            for other in &octants[i + 1..] {
+                assert_eq!(octant.intersection(other), None);
+            }
+        }
+        assert_eq!(union, aabb);
+        assert_eq!(volume, aabb.volume());
+    }
+
+    #[test]
+    fn penetration_pushes_along_the_shallowest_axis() {
+        let other = AABB::new(0.0, 0.0, 0.0, 4.0, 4.0, 4.0);
+        assert_eq!(AABB::new(3.5, 1.0, 1.0, 5.5, 3.0, 3.0).penetration(&other), Some(vec![0.5, 0.0, 0.0]));
+        assert_eq!(AABB::new(1.0, -1.0, 1.0, 3.0, 0.25, 3.0).penetration(&other), Some(vec![0.0, -0.25, 0.0]));
+        assert_eq!(AABB::new(1.0, 1.0, 3.5, 3.0, 3.0, 8.0).penetration(&other), Some(vec![0.0, 0.0, 0.5]));
+        assert_eq!(AABB::new(1.0, 1.0, -2.0, 3.0, 3.0, 0.5).penetration(&other), Some(vec![0.0, 0.0, -0.5]));
+    }
+
+    #[test]
+    fn penetration_ties_go_to_the_lowest_axis_and_touching_needs_no_push() {
+        let other = AABB::new(0.0, 0.0, 0.0, 4.0, 4.0, 4.0);
+        assert_eq!(AABB::new(3.0, 3.0, 1.0, 5.0, 5.0, 3.0).penetration(&other), Some(vec![1.0, 0.0, 0.0]));
+        assert_eq!(AABB::new(1.0, 3.0, 3.0, 3.0, 5.0, 5.0).penetration(&other), Some(vec![0.0, 1.0, 0.0]));
+        assert_eq!(AABB::new(1.0, 1.0, 1.0, 3.0, 3.0, 3.0).penetration(&other), Some(vec![3.0, 0.0, 0.0]));
+        assert_eq!(AABB::new(4.0, 0.0, 0.0, 5.0, 4.0, 4.0).penetration(&other), None);
+        assert_eq!(AABB::new(6.0, 0.0, 0.0, 7.0, 4.0, 4.0).penetration(&other), None);
+    }
+
+    #[test]
+    fn intersects_aabb_within_respects_reach() {
+        let aabb = AABB::new(4.0, 0.0, 0.0, 5.0, 1.0, 1.0);
+        let ray = Ray::new(0.0, 0.5, 0.5, 1.0, 0.0, 0.0);
+        assert!(ray.intersects_aabb_within(&aabb, 4.01));
+        assert!(ray.intersects_aabb_within(&aabb, 4.0));
+        assert!(!ray.intersects_aabb_within(&aabb, 3.99));
+        assert!(!Ray::new(0.0, 0.5, 0.5, -1.0, 0.0, 0.0).intersects_aabb_within(&aabb, 100.0));
+
+        let inside = Ray::new(4.5, 0.5, 0.5, 0.0, 1.0, 0.0);
+        assert!(inside.intersects_aabb_within(&aabb, 0.0));
+        assert!(!inside.intersects_aabb_within(&aabb, -1.0));
+    }
+
+    #[test]
+    fn lerp_clamps_to_the_endpoints() {
+        let a = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        let b = AABB::new(2.0, 4.0, -2.0, 6.0, 8.0, 0.0);
+        assert_eq!(a.lerp(&b, 0.0), a);
+        assert_eq!(a.lerp(&b, 1.0), b);
+        assert_eq!(a.lerp(&b, 0.5), AABB::new(1.0, 2.0, -1.0, 3.5, 4.5, 0.5));
+        assert_eq!(a.lerp(&b, -1.0), a);
+        assert_eq!(a.lerp(&b, 2.0), b);
+    }
+
+    #[test]
+    fn aabb_interval_reports_entry_and_exit() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        assert_eq!(Ray::new(-1.0, 0.5, 0.5, 1.0, 0.0, 0.0).aabb_interval(&aabb), Some((1.0, 2.0)));
+        assert_eq!(Ray::new(0.5, 0.5, 0.25, 0.0, 0.0, 1.0).aabb_interval(&aabb), Some((0.0, 0.75)));
+        assert_eq!(Ray::new(-1.0, 0.5, 0.5, 1.0, 0.0, 0.0).aabb_interval_js(&aabb), Some(vec![1.0, 2.0]));
+        assert_eq!(Ray::new(2.0, 0.5, 0.5, 1.0, 0.0, 0.0).aabb_interval(&aabb), None);
+    }
+
+    #[test]
+    fn aabb_interval_of_a_tangent_ray_is_the_touching_span() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        assert_eq!(Ray::new(-1.0, 1.0, 0.5, 1.0, 0.0, 0.0).aabb_interval(&aabb), Some((1.0, 2.0)));
+
+        // Touches only the edge at x = y = 1.
+        let (enter, exit) = Ray::new(0.0, 2.0, 0.5, 1.0, -1.0, 0.0).aabb_interval(&aabb).unwrap();
+        assert!((enter - std::f32::consts::SQRT_2).abs() < 1e-6);
+        assert!((exit - enter).abs() < 1e-6);
+        assert_eq!(Ray::new(0.0, 2.1, 0.5, 1.0, -1.0, 0.0).aabb_interval(&aabb), None);
+    }
+
+    #[test]
+    fn distance_between_boxes_combines_the_axis_gaps() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        // Gaps of 3 and 4 diagonally across the corner.
+        let diagonal = AABB::new(4.0, 5.0, 1.0, 6.0, 6.0, 2.0);
+        assert_eq!(aabb.distance(&diagonal), 5.0);
+        assert_eq!(diagonal.distance(&aabb), 5.0);
+        assert_eq!(aabb.distance(&AABB::new(3.0, 0.5, 0.5, 4.0, 2.0, 2.0)), 2.0);
+        assert_eq!(aabb.distance(&AABB::new(1.0, 0.0, 0.0, 2.0, 1.0, 1.0)), 0.0);
+        assert_eq!(aabb.distance(&AABB::new(0.5, 0.5, 0.5, 4.0, 2.0, 2.0)), 0.0);
+    }
+
+    #[test]
+    fn overlap_extents_are_negative_across_a_gap() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        assert_eq!(aabb.overlap_extents(&AABB::new(3.0, 0.5, 0.0, 4.0, 2.0, 0.25)), vec![-2.0, 0.5, 0.25]);
+        assert_eq!(aabb.overlap_extents(&aabb), vec![1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn approx_eq_includes_the_epsilon_boundary() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        let off = AABB::new(0.5, 0.0, 0.0, 1.0, 1.0, 1.5);
+        assert!(aabb.approx_eq(&off, 0.5));
+        assert!(!aabb.approx_eq(&off, 0.49));
+        assert_eq!(aabb, AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0));
+        assert_ne!(aabb, AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0001));
+
+        // Rays compare normalized directions.
+        let ray = Ray::new(0.0, 0.0, 0.0, 0.0, 0.0, 2.0);
+        assert!(ray.approx_eq(&Ray::new(0.25, 0.0, 0.0, 0.0, 0.0, 1.0), 0.25));
+        assert!(!ray.approx_eq(&Ray::new(0.25, 0.0, 0.0, 0.0, 0.0, 1.0), 0.24));
+        assert_eq!(ray, Ray::new(0.0, 0.0, 0.0, 0.0, 0.0, 5.0));
+    }
+
+    #[test]
+    fn hit_aabb_agrees_with_the_separate_queries() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        let ray = Ray::new(0.5, 3.0, 0.25, 0.0, -1.0, 0.0);
+        let hit = ray.hit_aabb(&aabb).unwrap();
+        assert_eq!(hit.t(), 2.0);
+        assert_eq!(Some(hit.t()), ray.distance_to_aabb(&aabb));
+        assert_eq!(hit.point(), vec![0.5, 1.0, 0.25]);
+        assert_eq!(hit.point(), ray.at(hit.t()));
+        assert_eq!(hit.normal(), vec![0.0, 1.0, 0.0]);
+        assert_eq!(ray.intersect_aabb_face(&aabb), Some((2.0, 3)));
+
+        let inside = Ray::new(0.5, 0.5, 0.5, 1.0, 0.0, 0.0).hit_aabb(&aabb).unwrap();
+        assert_eq!((inside.t(), inside.point(), inside.normal()), (0.0, vec![0.5, 0.5, 0.5], vec![0.0; 3]));
+        assert!(Ray::new(0.5, 3.0, 0.25, 0.0, 1.0, 0.0).hit_aabb(&aabb).is_none());
+    }
+
+    #[test]
+    fn march_aabb_samples_the_span_inside_the_box() {
+        let aabb = AABB::new(2.0, 0.0, 0.0, 6.0, 1.0, 1.0);
+        let ray = Ray::new(0.0, 0.5, 0.5, 1.0, 0.0, 0.0);
+        // A span of 4 at a step of 0.5 includes both ends.
+        let samples = ray.march_aabb(&aabb, 0.5, 100);
+        assert_eq!(samples.len(), 9 * 3);
+        assert_eq!(&samples[..3], &[2.0, 0.5, 0.5]);
+        assert_eq!(&samples[24..], &[6.0, 0.5, 0.5]);
+        assert_eq!(ray.march_aabb(&aabb, 0.3, 100).len(), 14 * 3);
+
+        assert_eq!(ray.march_aabb(&aabb, 0.5, 3), vec![2.0, 0.5, 0.5, 2.5, 0.5, 0.5, 3.0, 0.5, 0.5]);
+        assert_eq!(ray.march_aabb(&aabb, 0.0, 100), vec![2.0, 0.5, 0.5]);
+        assert_eq!(ray.march_aabb(&aabb, 10.0, 100), vec![2.0, 0.5, 0.5]);
+        assert!(Ray::new(0.0, 2.0, 0.5, 1.0, 0.0, 0.0).march_aabb(&aabb, 0.5, 100).is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::Chunk;
+
+#[wasm_bindgen]
+impl Chunk {
+    /// Coarser copy at half the resolution, covering the same world extent
+    /// with voxels twice as large. Each 2x2x2 block becomes air if most of it
+    /// is air, and otherwise its most common solid id, lowest id on ties. A
+    /// chunk of size 1 is returned as is.
+    pub fn downsample(&self) -> Chunk {
+        let size = self.size();
+        if size < 2 {
+            return self.clone();
+        }
+
+        let half = size / 2;
+        let mut voxels = Vec::with_capacity((half * half * half) as usize);
+        let mut counts = HashMap::new();
+
+        for z in 0..half as i32 {
+            for y in 0..half as i32 {
+                for x in 0..half as i32 {
+                    counts.clear();
+                    let mut air = 0;
+                    for corner in 0..8 {
+                        let id = self.get(2 * x + (corner & 1), 2 * y + (corner >> 1 & 1), 2 * z + (corner >> 2));
+                        if id == 0 {
+                            air += 1;
+                        } else {
+                            *counts.entry(id).or_insert(0) += 1;
+                        }
+                    }
+
+                    let id = if air > 4 {
+                        0
+                    } else {
+                        counts
+                            .iter()
+                            .max_by_key(|&(&id, &count)| (count, std::cmp::Reverse(id)))
+                            .map_or(0, |(&id, _)| id)
+                    };
+                    voxels.push(id);
+                }
+            }
+        }
+
+        let mut chunk = Chunk::from_voxels(self.bounds().min, half, voxels);
+        chunk.set_voxel_size(self.voxel_size() * size as f32 / half as f32);
+        chunk
+    }
+
+    /// Applies `downsample` `levels` times.
+    pub fn downsample_n(&self, levels: u32) -> Chunk {
+        (0..levels).fold(self.clone(), |chunk, _| chunk.downsample())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AABB;
+
+    // Sets the 2x2x2 block at the origin from `ids`, in corner order.
+    fn first_block(ids: [u16; 8]) -> Chunk {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        for (corner, id) in (0..8).zip(ids) {
+            chunk.set(corner & 1, corner >> 1 & 1, corner >> 2, id);
+        }
+        chunk
+    }
+
+    #[test]
+    fn a_single_id_survives_everywhere() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        chunk.fill(6);
+        let coarse = chunk.downsample();
+        assert_eq!(coarse.size(), 16);
+        assert_eq!(coarse.voxel_size(), 2.0);
+        assert_eq!(coarse.solid_count(), 16 * 16 * 16);
+        assert_eq!((coarse.get(0, 0, 0), coarse.get(15, 15, 15), coarse.get(3, 9, 12)), (6, 6, 6));
+    }
+
+    #[test]
+    fn a_mostly_air_block_becomes_air() {
+        let coarse = first_block([1, 0, 2, 0, 0, 3, 0, 0]).downsample();
+        assert_eq!(coarse.get(0, 0, 0), 0);
+        assert!(coarse.is_empty());
+    }
+
+    #[test]
+    fn an_even_split_between_air_and_solid_stays_solid() {
+        let coarse = first_block([0, 5, 0, 5, 5, 0, 5, 0]).downsample();
+        assert_eq!(coarse.get(0, 0, 0), 5);
+        assert_eq!(coarse.solid_count(), 1);
+    }
+
+    #[test]
+    fn tied_ids_resolve_to_the_lowest() {
+        assert_eq!(first_block([7, 7, 3, 3, 0, 0, 0, 0]).downsample().get(0, 0, 0), 3);
+        assert_eq!(first_block([9, 9, 9, 4, 4, 0, 0, 0]).downsample().get(0, 0, 0), 9);
+        assert_eq!(first_block([2, 8, 8, 2, 8, 2, 8, 2]).downsample().get(0, 0, 0), 2);
+    }
+
+    #[test]
+    fn downsample_n_keeps_the_world_bounds() {
+        let mut chunk = Chunk::new(32.0, -32.0, 0.0);
+        chunk.fill(1);
+        let coarse = chunk.downsample_n(2);
+        assert_eq!(coarse.size(), 8);
+        assert_eq!(coarse.voxel_size(), 4.0);
+        assert_eq!(coarse.bounds(), chunk.bounds());
+        assert_eq!(coarse.bounds(), AABB::new(32.0, -32.0, 0.0, 64.0, 0.0, 32.0));
+        assert_eq!(chunk.downsample_n(0).size(), 32);
+        assert_eq!(chunk.downsample_n(10).size(), 1);
+    }
+}
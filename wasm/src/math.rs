@@ -0,0 +1,224 @@
+// Vector and matrix types behind the `f32` geometry. They come from nalgebra
+// by default and from glam with the `glam` feature; the helpers below paper
+// over the two crates' differently named or differently borrowed methods so
+// the geometry code reads the same either way.
+
+#[cfg(not(feature = "glam"))]
+mod backend {
+    use nalgebra as na;
+
+    pub type Point3 = na::Point3<f32>;
+    pub type Vector3 = na::Vector3<f32>;
+    pub type Matrix3 = na::Matrix3<f32>;
+    pub type Matrix4 = na::Matrix4<f32>;
+
+    pub fn point(x: f32, y: f32, z: f32) -> Point3 {
+        Point3::new(x, y, z)
+    }
+
+    pub fn vector(x: f32, y: f32, z: f32) -> Vector3 {
+        Vector3::new(x, y, z)
+    }
+
+    pub fn splat(value: f32) -> Vector3 {
+        Vector3::repeat(value)
+    }
+
+    pub fn zero() -> Vector3 {
+        Vector3::zeros()
+    }
+
+    pub fn to_vector(point: Point3) -> Vector3 {
+        point.coords
+    }
+
+    pub fn to_point(vector: Vector3) -> Point3 {
+        Point3::from(vector)
+    }
+
+    pub fn min(a: Point3, b: Point3) -> Point3 {
+        a.inf(&b)
+    }
+
+    pub fn max(a: Point3, b: Point3) -> Point3 {
+        a.sup(&b)
+    }
+
+    pub fn center(a: Point3, b: Point3) -> Point3 {
+        na::center(&a, &b)
+    }
+
+    pub fn lerp(a: Point3, b: Point3, t: f32) -> Point3 {
+        a.lerp(&b, t)
+    }
+
+    pub fn distance(a: Point3, b: Point3) -> f32 {
+        na::distance(&a, &b)
+    }
+
+    pub fn distance_squared(a: Point3, b: Point3) -> f32 {
+        na::distance_squared(&a, &b)
+    }
+
+    pub fn dot(a: Vector3, b: Vector3) -> f32 {
+        a.dot(&b)
+    }
+
+    pub fn cross(a: Vector3, b: Vector3) -> Vector3 {
+        a.cross(&b)
+    }
+
+    pub fn length(v: Vector3) -> f32 {
+        v.norm()
+    }
+
+    pub fn length_squared(v: Vector3) -> f32 {
+        v.norm_squared()
+    }
+
+    pub fn matrix3(columns: &[f32]) -> Matrix3 {
+        Matrix3::from_column_slice(columns)
+    }
+
+    pub fn matrix4(columns: &[f32]) -> Matrix4 {
+        Matrix4::from_column_slice(columns)
+    }
+
+    /// Applies a homogeneous transform, dividing by `w`.
+    pub fn transform_point(matrix: &Matrix4, point: Point3) -> Point3 {
+        matrix.transform_point(&point)
+    }
+}
+
+#[cfg(feature = "glam")]
+mod backend {
+    pub type Point3 = glam::Vec3;
+    pub type Vector3 = glam::Vec3;
+    pub type Matrix3 = glam::Mat3;
+    pub type Matrix4 = glam::Mat4;
+
+    pub fn point(x: f32, y: f32, z: f32) -> Point3 {
+        glam::Vec3::new(x, y, z)
+    }
+
+    pub fn vector(x: f32, y: f32, z: f32) -> Vector3 {
+        glam::Vec3::new(x, y, z)
+    }
+
+    pub fn splat(value: f32) -> Vector3 {
+        glam::Vec3::splat(value)
+    }
+
+    pub fn zero() -> Vector3 {
+        glam::Vec3::ZERO
+    }
+
+    pub fn to_vector(point: Point3) -> Vector3 {
+        point
+    }
+
+    pub fn to_point(vector: Vector3) -> Point3 {
+        vector
+    }
+
+    pub fn min(a: Point3, b: Point3) -> Point3 {
+        a.min(b)
+    }
+
+    pub fn max(a: Point3, b: Point3) -> Point3 {
+        a.max(b)
+    }
+
+    pub fn center(a: Point3, b: Point3) -> Point3 {
+        (a + b) * 0.5
+    }
+
+    pub fn lerp(a: Point3, b: Point3, t: f32) -> Point3 {
+        a.lerp(b, t)
+    }
+
+    pub fn distance(a: Point3, b: Point3) -> f32 {
+        a.distance(b)
+    }
+
+    pub fn distance_squared(a: Point3, b: Point3) -> f32 {
+        a.distance_squared(b)
+    }
+
+    pub fn dot(a: Vector3, b: Vector3) -> f32 {
+        a.dot(b)
+    }
+
+    pub fn cross(a: Vector3, b: Vector3) -> Vector3 {
+        a.cross(b)
+    }
+
+    pub fn length(v: Vector3) -> f32 {
+        v.length()
+    }
+
+    pub fn length_squared(v: Vector3) -> f32 {
+        v.length_squared()
+    }
+
+    pub fn matrix3(columns: &[f32]) -> Matrix3 {
+        glam::Mat3::from_cols_slice(columns)
+    }
+
+    pub fn matrix4(columns: &[f32]) -> Matrix4 {
+        glam::Mat4::from_cols_slice(columns)
+    }
+
+    /// Applies a homogeneous transform, dividing by `w`.
+    pub fn transform_point(matrix: &Matrix4, point: Point3) -> Point3 {
+        matrix.project_point3(point)
+    }
+}
+
+pub use backend::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matrices_read_columns_first() {
+        let m = matrix3(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+        assert_eq!(m * vector(1.0, 0.0, 0.0), vector(1.0, 2.0, 3.0));
+        assert_eq!(m * vector(0.0, 0.0, 1.0), vector(7.0, 8.0, 9.0));
+
+        let translate = matrix4(&[
+            1.0, 0.0, 0.0, 0.0,
+            0.0, 1.0, 0.0, 0.0,
+            0.0, 0.0, 1.0, 0.0,
+            5.0, 6.0, 7.0, 1.0,
+        ]);
+        assert_eq!(transform_point(&translate, point(1.0, 1.0, 1.0)), point(6.0, 7.0, 8.0));
+    }
+
+    #[test]
+    fn transform_point_divides_by_w() {
+        // w = 2 for every point, so the result is half the linear part.
+        let m = matrix4(&[
+            1.0, 0.0, 0.0, 0.0,
+            0.0, 1.0, 0.0, 0.0,
+            0.0, 0.0, 1.0, 0.0,
+            0.0, 0.0, 0.0, 2.0,
+        ]);
+        assert_eq!(transform_point(&m, point(2.0, 4.0, 6.0)), point(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn helpers_agree_across_backends() {
+        let (a, b) = (point(0.0, 4.0, 1.0), point(3.0, 0.0, 1.0));
+        assert_eq!(min(a, b), point(0.0, 0.0, 1.0));
+        assert_eq!(max(a, b), point(3.0, 4.0, 1.0));
+        assert_eq!(center(a, b), point(1.5, 2.0, 1.0));
+        assert_eq!(lerp(a, b, 0.5), center(a, b));
+        assert_eq!(distance(a, b), 5.0);
+        assert_eq!(distance_squared(a, b), 25.0);
+        assert_eq!(cross(vector(1.0, 0.0, 0.0), vector(0.0, 1.0, 0.0)), vector(0.0, 0.0, 1.0));
+        assert_eq!(length(to_vector(b) - vector(3.0, -4.0, 1.0)), 4.0);
+        assert_eq!(to_point(splat(2.0)), point(2.0, 2.0, 2.0));
+    }
+}
@@ -0,0 +1,542 @@
+use wasm_bindgen::prelude::*;
+
+use crate::Chunk;
+
+/// Triangle mesh with flat per-vertex positions and normals and a `u32`
+/// index buffer, ready to upload as vertex attributes. Meshes built with
+/// ambient occlusion also carry one AO level per vertex.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct MeshData {
+    positions: Vec<f32>,
+    normals: Vec<f32>,
+    indices: Vec<u32>,
+    ao: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl MeshData {
+    #[wasm_bindgen(getter)]
+    pub fn positions(&self) -> Vec<f32> {
+        self.positions.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn normals(&self) -> Vec<f32> {
+        self.normals.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn indices(&self) -> Vec<u32> {
+        self.indices.clone()
+    }
+
+    /// Per-vertex AO level from 0 (fully occluded) to 3 (open), or empty when
+    /// the mesh was built without ambient occlusion.
+    #[wasm_bindgen(getter)]
+    pub fn ao(&self) -> Vec<u8> {
+        self.ao.clone()
+    }
+
+    pub fn quad_count(&self) -> usize {
+        self.indices.len() / 6
+    }
+}
+
+impl MeshData {
+    fn push_quad(&mut self, corners: [[f32; 3]; 4], normal: [f32; 3], flip: bool, ao: Option<[u8; 4]>) {
+        let base = (self.positions.len() / 3) as u32;
+        for corner in corners {
+            self.positions.extend_from_slice(&corner);
+            self.normals.extend_from_slice(&normal);
+        }
+
+        // Split along the brighter diagonal so occlusion interpolates
+        // symmetrically across the quad.
+        let along_13 = ao.is_some_and(|ao| ao[0] + ao[2] < ao[1] + ao[3]);
+        if let Some(ao) = ao {
+            self.ao.extend_from_slice(&ao);
+        }
+
+        let order: [u32; 6] = match (flip, along_13) {
+            (false, false) => [0, 1, 2, 0, 2, 3],
+            (true, false) => [0, 2, 1, 0, 3, 2],
+            (false, true) => [1, 2, 3, 1, 3, 0],
+            (true, true) => [1, 3, 2, 1, 0, 3],
+        };
+        self.indices.extend(order.iter().map(|i| base + i));
+    }
+}
+
+#[wasm_bindgen]
+impl Chunk {
+    /// Meshes the chunk relative to its origin, scaled by `voxel_size`,
+    /// merging coplanar faces of the same id into larger quads. Faces on the
+    /// chunk border are always emitted.
+    pub fn greedy_mesh(&self) -> MeshData {
+        greedy_mesh(self, false, |x, y, z| self.get(x, y, z))
+    }
+
+    /// Like `greedy_mesh`, with a per-vertex AO level computed from the two
+    /// side voxels and the diagonal voxel next to each face corner. Faces only
+    /// merge when their corner levels match, so this produces more quads.
+    pub fn greedy_mesh_ao(&self) -> MeshData {
+        greedy_mesh(self, true, |x, y, z| self.get(x, y, z))
+    }
+
+    /// Occupancy of the voxel layer on one face of the chunk, numbered 0..5
+    /// for -X, +X, -Y, +Y, -Z, +Z, as a bitmask with one bit per cell (set
+    /// for solid), least significant bit first. For a face along axis `d`,
+    /// cell `(u, v)` of the layer is bit `u + size * v`, where `u` and `v`
+    /// are the coordinates on axes `(d + 1) % 3` and `(d + 2) % 3`. A face
+    /// outside 0..5 gives an empty mask, which `mesh_with_masks` reads as air.
+    pub fn boundary_mask(&self, face: u8) -> Vec<u8> {
+        if face >= 6 {
+            return Vec::new();
+        }
+        let size = self.size() as i32;
+        let (d, u, v) = face_axes(face);
+        let layer = if face & 1 == 0 { 0 } else { size - 1 };
+
+        let mut mask = vec![0u8; ((size * size) as usize).div_ceil(8)];
+        for j in 0..size {
+            for i in 0..size {
+                let mut cell = [0; 3];
+                cell[d] = layer;
+                cell[u] = i;
+                cell[v] = j;
+                if self.get(cell[0], cell[1], cell[2]) != 0 {
+                    let bit = (i + size * j) as usize;
+                    mask[bit / 8] |= 1 << (bit % 8);
+                }
+            }
+        }
+        mask
+    }
+
+    /// JS form of [`Chunk::mesh_with_masks`], taking the six masks as
+    /// separate arguments.
+    #[wasm_bindgen(js_name = mesh_with_masks)]
+    pub fn mesh_with_masks_js(
+        &self,
+        neg_x: &[u8],
+        pos_x: &[u8],
+        neg_y: &[u8],
+        pos_y: &[u8],
+        neg_z: &[u8],
+        pos_z: &[u8],
+    ) -> MeshData {
+        self.mesh_with_masks([neg_x, pos_x, neg_y, pos_y, neg_z, pos_z])
+    }
+}
+
+impl Chunk {
+    /// Like `greedy_mesh`, but border faces are culled against the adjacent
+    /// chunks' boundary voxels. A missing neighbor is treated as air.
+    ///
+    /// Optional borrowed chunks can't cross the wasm boundary, so this is only
+    /// available from Rust.
+    pub fn mesh_with_neighbors(
+        &self,
+        neg_x: Option<&Chunk>,
+        pos_x: Option<&Chunk>,
+        neg_y: Option<&Chunk>,
+        pos_y: Option<&Chunk>,
+        neg_z: Option<&Chunk>,
+        pos_z: Option<&Chunk>,
+    ) -> MeshData {
+        let size = self.size() as i32;
+        let across = |neighbor: Option<&Chunk>, x: i32, y: i32, z: i32| neighbor.map_or(0, |chunk| chunk.get(x, y, z));
+
+        greedy_mesh(self, false, |x, y, z| match (x, y, z) {
+            (-1, _, _) => across(neg_x, size - 1, y, z),
+            (x, _, _) if x == size => across(pos_x, 0, y, z),
+            (_, -1, _) => across(neg_y, x, size - 1, z),
+            (_, y, _) if y == size => across(pos_y, x, 0, z),
+            (_, _, -1) => across(neg_z, x, y, size - 1),
+            (_, _, z) if z == size => across(pos_z, x, y, 0),
+            _ => self.get(x, y, z),
+        })
+    }
+
+    /// Like `mesh_with_neighbors`, with each neighbor given only by the
+    /// `boundary_mask` of its face touching this chunk, in -X, +X, -Y, +Y,
+    /// -Z, +Z order: `masks[0]` is the -X neighbor's face 1. An empty mask
+    /// stands for a missing neighbor.
+    pub fn mesh_with_masks(&self, masks: [&[u8]; 6]) -> MeshData {
+        let size = self.size() as i32;
+        // Solid bits read back as id 1; the mesher only asks whether a
+        // neighbor cell is air.
+        let across = |face: u8, cell: [i32; 3]| {
+            let (_, u, v) = face_axes(face);
+            let bit = (cell[u] + size * cell[v]) as usize;
+            masks[face as usize].get(bit / 8).map_or(0, |byte| (byte >> (bit % 8) & 1) as u16)
+        };
+
+        greedy_mesh(self, false, |x, y, z| match (x, y, z) {
+            (-1, _, _) => across(0, [x, y, z]),
+            (x, _, _) if x == size => across(1, [x, y, z]),
+            (_, -1, _) => across(2, [x, y, z]),
+            (_, y, _) if y == size => across(3, [x, y, z]),
+            (_, _, -1) => across(4, [x, y, z]),
+            (_, _, z) if z == size => across(5, [x, y, z]),
+            _ => self.get(x, y, z),
+        })
+    }
+}
+
+// Normal axis of a face numbered 0..5 for -X, +X, -Y, +Y, -Z, +Z, and the two
+// axes spanning it.
+fn face_axes(face: u8) -> (usize, usize, usize) {
+    let d = face as usize / 2;
+    (d, (d + 1) % 3, (d + 2) % 3)
+}
+
+/// Greedy-meshes each chunk on the rayon thread pool, producing the same
+/// output as calling `greedy_mesh` on each in turn. Native only: wasm builds
+/// have no thread pool, so this is absent on that target.
+#[cfg(all(feature = "rayon", not(target_arch = "wasm32")))]
+pub fn mesh_chunks_parallel(chunks: &[Chunk]) -> Vec<MeshData> {
+    use rayon::prelude::*;
+
+    chunks.par_iter().map(Chunk::greedy_mesh).collect()
+}
+
+// Greedy meshing over the chunk's cells. `sample` is queried for neighbors
+// one step outside the chunk as well as for cells inside it; a face is emitted
+// wherever a solid voxel borders air. Front faces wind counter-clockwise.
+//
+// Mask entries hold the voxel id in the low 16 bits and, with `ao`, the four
+// corner levels packed two bits each above it, so merging compares both.
+pub(crate) fn greedy_mesh(chunk: &Chunk, ao: bool, sample: impl Fn(i32, i32, i32) -> u16) -> MeshData {
+    let size = chunk.size() as i32;
+    let n = size as usize;
+    let scale = chunk.voxel_size();
+    let mut mesh = MeshData::default();
+    let mut mask = vec![0u32; n * n];
+
+    for d in 0..3 {
+        let u = (d + 1) % 3;
+        let v = (d + 2) % 3;
+
+        for sign in [-1i32, 1] {
+            for k in 0..size {
+                for j in 0..size {
+                    for i in 0..size {
+                        let mut cell = [0; 3];
+                        cell[d] = k;
+                        cell[u] = i;
+                        cell[v] = j;
+                        let id = chunk.get(cell[0], cell[1], cell[2]);
+
+                        let mut neighbor = cell;
+                        neighbor[d] += sign;
+                        let covered = sample(neighbor[0], neighbor[1], neighbor[2]) != 0;
+
+                        mask[i as usize + j as usize * n] = if id == 0 || covered {
+                            0
+                        } else if ao {
+                            let levels = corner_ao(&sample, neighbor, u, v);
+                            let packed = levels.iter().enumerate().fold(0, |acc, (c, &l)| acc | (l as u32) << (c * 2));
+                            id as u32 | packed << 16
+                        } else {
+                            id as u32
+                        };
+                    }
+                }
+
+                let plane = if sign > 0 { k + 1 } else { k } as f32;
+                let mut normal = [0.0; 3];
+                normal[d] = sign as f32;
+
+                for j in 0..n {
+                    let mut i = 0;
+                    while i < n {
+                        let id = mask[i + j * n];
+                        if id == 0 {
+                            i += 1;
+                            continue;
+                        }
+
+                        let mut width = 1;
+                        while i + width < n && mask[i + width + j * n] == id {
+                            width += 1;
+                        }
+
+                        let mut height = 1;
+                        'grow: while j + height < n {
+                            for offset in 0..width {
+                                if mask[i + offset + (j + height) * n] != id {
+                                    break 'grow;
+                                }
+                            }
+                            height += 1;
+                        }
+
+                        for row in 0..height {
+                            mask[i + (j + row) * n..i + width + (j + row) * n].fill(0);
+                        }
+
+                        let corner = |du: usize, dv: usize| {
+                            let mut p = [0.0; 3];
+                            p[d] = plane * scale;
+                            p[u] = (i + du) as f32 * scale;
+                            p[v] = (j + dv) as f32 * scale;
+                            p
+                        };
+                        mesh.push_quad(
+                            [corner(0, 0), corner(width, 0), corner(width, height), corner(0, height)],
+                            normal,
+                            sign < 0,
+                            ao.then(|| std::array::from_fn(|c| (id >> (16 + c * 2)) as u8 & 3)),
+                        );
+
+                        i += width;
+                    }
+                }
+            }
+        }
+    }
+
+    mesh
+}
+
+// AO levels for the corners of a face whose open side is the cell `layer`, in
+// the quad's corner order (-u-v, +u-v, +u+v, -u+v). A corner boxed in by both
+// side voxels is fully occluded regardless of the diagonal.
+fn corner_ao(sample: &impl Fn(i32, i32, i32) -> u16, layer: [i32; 3], u: usize, v: usize) -> [u8; 4] {
+    let solid = |du: i32, dv: i32| {
+        let mut cell = layer;
+        cell[u] += du;
+        cell[v] += dv;
+        (sample(cell[0], cell[1], cell[2]) != 0) as u8
+    };
+
+    [(-1, -1), (1, -1), (1, 1), (-1, 1)].map(|(du, dv)| {
+        let side_u = solid(du, 0);
+        let side_v = solid(0, dv);
+        if side_u == 1 && side_v == 1 {
+            0
+        } else {
+            3 - side_u - side_v - solid(du, dv)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math;
+
+    #[test]
+    fn masks_match_neighbor_chunks() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        let mut neighbors: Vec<Chunk> = (0..6).map(|_| Chunk::new(0.0, 0.0, 0.0)).collect();
+        for z in 0..32 {
+            for y in 0..32 {
+                for x in 0..32 {
+                    if (x * 7 + y * 3 + z * 5) % 4 == 0 {
+                        chunk.set(x, y, z, 1 + ((x + y) % 3) as u16);
+                    }
+                    for (i, neighbor) in neighbors.iter_mut().enumerate() {
+                        if (x + 2 * y + 3 * z + i as i32) % 3 != 0 {
+                            neighbor.set(x, y, z, 9);
+                        }
+                    }
+                }
+            }
+        }
+        neighbors[3].fill(5);
+
+        let n = &neighbors;
+        let [neg_x, pos_x, neg_y, pos_y, neg_z, pos_z] = std::array::from_fn(|face| Some(&n[face]));
+        let expected = chunk.mesh_with_neighbors(neg_x, pos_x, neg_y, pos_y, neg_z, pos_z);
+        let masks: Vec<Vec<u8>> = (0..6).map(|face| n[face].boundary_mask(face as u8 ^ 1)).collect();
+        assert_eq!(masks[0].len(), 128);
+        let mesh = chunk.mesh_with_masks(std::array::from_fn(|face| masks[face].as_slice()));
+        assert_eq!(mesh.positions(), expected.positions());
+        assert_eq!(mesh.indices(), expected.indices());
+
+        let open = chunk.mesh_with_masks([&[]; 6]);
+        assert_eq!(open.positions(), chunk.mesh_with_neighbors(None, None, None, None, None, None).positions());
+    }
+
+    #[test]
+    fn solid_neighbor_mask_culls_the_shared_face() {
+        let mut solid = Chunk::new(0.0, 0.0, 0.0);
+        solid.fill(1);
+        let mask = solid.boundary_mask(1);
+        assert!(mask.iter().all(|&byte| byte == 0xff));
+
+        let mesh = solid.mesh_with_masks([&mask, &[], &[], &[], &[], &[]]);
+        assert_eq!(mesh.quad_count(), 5);
+        let reference = solid.mesh_with_neighbors(Some(&solid), None, None, None, None, None);
+        assert_eq!(mesh.positions(), reference.positions());
+        assert!(mesh.normals().chunks(3).all(|normal| normal != [-1.0, 0.0, 0.0]));
+    }
+
+    #[test]
+    fn boundary_mask_bit_layout() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        chunk.set(0, 3, 5, 1);
+        let bit = 3 + 32 * 5;
+        let mask = chunk.boundary_mask(0);
+        assert_eq!(mask[bit / 8], 1 << (bit % 8));
+        assert_eq!(mask.iter().map(|byte| byte.count_ones()).sum::<u32>(), 1);
+        assert!(chunk.boundary_mask(1).iter().all(|&byte| byte == 0));
+        assert!(chunk.boundary_mask(6).is_empty());
+        assert!(chunk.boundary_mask(u8::MAX).is_empty());
+    }
+
+    // Every triangle winds counter-clockwise seen from the side its normal
+    // points to.
+    fn assert_front_facing(mesh: &MeshData) {
+        let (positions, normals) = (mesh.positions(), mesh.normals());
+        let vertex = |i: u32| {
+            let p = &positions[i as usize * 3..][..3];
+            math::point(p[0], p[1], p[2])
+        };
+        for triangle in mesh.indices().chunks(3) {
+            let (a, b, c) = (vertex(triangle[0]), vertex(triangle[1]), vertex(triangle[2]));
+            let n = &normals[triangle[0] as usize * 3..][..3];
+            assert!(math::dot(math::cross(b - a, c - a), math::vector(n[0], n[1], n[2])) > 0.0);
+        }
+    }
+
+    #[test]
+    fn solid_cube_meshes_to_six_quads() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        for z in 4..6 {
+            for y in 4..6 {
+                for x in 4..6 {
+                    chunk.set(x, y, z, 1);
+                }
+            }
+        }
+        let mesh = chunk.greedy_mesh();
+        assert_eq!(mesh.quad_count(), 6);
+        assert_eq!((mesh.positions().len(), mesh.indices().len()), (6 * 4 * 3, 6 * 6));
+        assert_front_facing(&mesh);
+
+        // A different id next to it can't merge, so the cube's +X face stays.
+        chunk.set(6, 4, 4, 2);
+        assert!(chunk.greedy_mesh().quad_count() > 6);
+        assert_front_facing(&chunk.greedy_mesh());
+
+        let mut full = Chunk::new(0.0, 0.0, 0.0);
+        full.fill(3);
+        assert_eq!(full.greedy_mesh().quad_count(), 6);
+        assert_eq!(Chunk::new(0.0, 0.0, 0.0).greedy_mesh().quad_count(), 0);
+    }
+
+    #[test]
+    fn adjacent_solid_chunks_share_no_faces() {
+        let mut a = Chunk::new(0.0, 0.0, 0.0);
+        a.fill(1);
+        let mut b = Chunk::new(32.0, 0.0, 0.0);
+        b.fill(1);
+
+        let left = a.mesh_with_neighbors(None, Some(&b), None, None, None, None);
+        assert_eq!(left.quad_count(), 5);
+        assert!(left.normals().chunks(3).all(|normal| normal != [1.0, 0.0, 0.0]));
+        let right = b.mesh_with_neighbors(Some(&a), None, None, None, None, None);
+        assert_eq!(right.quad_count(), 5);
+        assert!(right.normals().chunks(3).all(|normal| normal != [-1.0, 0.0, 0.0]));
+
+        let enclosed = a.mesh_with_neighbors(Some(&b), Some(&b), Some(&b), Some(&b), Some(&b), Some(&b));
+        assert_eq!(enclosed.quad_count(), 0);
+    }
+
+    // Quads whose four normals equal `normal` and whose vertices all sit at
+    // `height` along that normal's axis, as quad indices.
+    fn quads_facing(mesh: &MeshData, normal: [f32; 3], axis: usize, height: f32) -> Vec<usize> {
+        let (positions, normals) = (mesh.positions(), mesh.normals());
+        (0..mesh.quad_count())
+            .filter(|&q| {
+                (q * 4..q * 4 + 4).all(|v| normals[v * 3..][..3] == normal && positions[v * 3 + axis] == height)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ao_darkens_the_inner_corner_of_an_l() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        chunk.set(0, 0, 0, 1);
+        chunk.set(1, 0, 0, 1);
+        chunk.set(0, 1, 0, 1);
+        let mesh = chunk.greedy_mesh_ao();
+        assert_front_facing(&mesh);
+        let (positions, ao) = (mesh.positions(), mesh.ao());
+        assert_eq!(ao.len() * 3, positions.len());
+
+        // Top of (1, 0, 0): the wall of (0, 1, 0) next to the x = 1 edge
+        // occludes one side, the x = 2 edge is open.
+        let top = quads_facing(&mesh, [0.0, 1.0, 0.0], 1, 1.0);
+        assert_eq!(top.len(), 1);
+        for v in top[0] * 4..top[0] * 4 + 4 {
+            let expected = if positions[v * 3] == 1.0 { 2 } else { 3 };
+            assert_eq!(ao[v], expected, "vertex at {:?}", &positions[v * 3..][..3]);
+        }
+        assert!(chunk.greedy_mesh().ao().is_empty());
+
+        let mut slab = Chunk::new(0.0, 0.0, 0.0);
+        for z in 0..32 {
+            for x in 0..32 {
+                slab.set(x, 0, z, 1);
+            }
+        }
+        assert_eq!(slab.greedy_mesh_ao().quad_count(), 6);
+    }
+
+    #[test]
+    fn ao_splits_quads_along_the_brighter_diagonal() {
+        // Only the diagonal voxel above the +z+x corner of (5, 5, 5)'s top.
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        chunk.set(5, 5, 5, 1);
+        chunk.set(6, 6, 6, 1);
+        let mesh = chunk.greedy_mesh_ao();
+        let (positions, ao, indices) = (mesh.positions(), mesh.ao(), mesh.indices());
+
+        let top = quads_facing(&mesh, [0.0, 1.0, 0.0], 1, 6.0);
+        assert_eq!(top.len(), 1);
+        let base = top[0] * 4;
+        // Corner order is -u-v, +u-v, +u+v, -u+v with u = z and v = x.
+        assert_eq!(&ao[base..base + 4], &[3, 3, 2, 3]);
+        assert_eq!(&positions[(base + 2) * 3..][..3], &[6.0, 6.0, 6.0]);
+        let expected: Vec<u32> = [1, 2, 3, 1, 3, 0].iter().map(|i| base as u32 + i).collect();
+        assert_eq!(&indices[top[0] * 6..][..6], expected.as_slice());
+
+        // The unoccluded top of (6, 6, 6) keeps the default split.
+        let plain = quads_facing(&mesh, [0.0, 1.0, 0.0], 1, 7.0);
+        assert_eq!(&ao[plain[0] * 4..][..4], &[3, 3, 3, 3]);
+        assert_eq!(indices[plain[0] * 6], plain[0] as u32 * 4);
+    }
+
+    #[cfg(all(feature = "rayon", not(target_arch = "wasm32")))]
+    #[test]
+    fn parallel_meshing_matches_serial() {
+        let mut next = crate::test_util::lcg(12345);
+        let chunks: Vec<Chunk> = (0..24)
+            .map(|c| {
+                let mut chunk = Chunk::new(c as f32 * 32.0, 0.0, 0.0);
+                for i in 0..chunk.len() as i32 {
+                    let bits = next();
+                    if bits >> 29 == 0 {
+                        chunk.set(i % 32, i / 32 % 32, i / 1024, (bits >> 20 & 3) as u16 + 1);
+                    }
+                }
+                chunk
+            })
+            .collect();
+
+        let parallel = mesh_chunks_parallel(&chunks);
+        assert_eq!(parallel.len(), chunks.len());
+        for (chunk, mesh) in chunks.iter().zip(&parallel) {
+            let serial = chunk.greedy_mesh();
+            assert!(serial.quad_count() > 0);
+            assert_eq!(serial.positions(), mesh.positions());
+            assert_eq!(serial.normals(), mesh.normals());
+            assert_eq!(serial.indices(), mesh.indices());
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use wasm_bindgen::prelude::*;
+
+/// Largest coordinate a Morton code can hold on each axis (2^21 - 1).
+pub const MORTON_MAX_COORD: u32 = (1 << 21) - 1;
+
+/// Interleaves the low 21 bits of each coordinate into a Z-order code, with x
+/// in the lowest bit. Higher bits are discarded.
+#[wasm_bindgen]
+pub fn morton_encode(x: u32, y: u32, z: u32) -> u64 {
+    spread_bits(x) | (spread_bits(y) << 1) | (spread_bits(z) << 2)
+}
+
+/// Inverse of `morton_encode`, returning `[x, y, z]`.
+#[wasm_bindgen]
+pub fn morton_decode(code: u64) -> Vec<u32> {
+    vec![compact_bits(code), compact_bits(code >> 1), compact_bits(code >> 2)]
+}
+
+// Moves bit `i` of a 21-bit value to bit `3 * i`.
+fn spread_bits(value: u32) -> u64 {
+    let mut x = (value & MORTON_MAX_COORD) as u64;
+    x = (x | (x << 32)) & 0x001f_0000_0000_ffff;
+    x = (x | (x << 16)) & 0x001f_0000_ff00_00ff;
+    x = (x | (x << 8)) & 0x100f_00f0_0f00_f00f;
+    x = (x | (x << 4)) & 0x10c3_0c30_c30c_30c3;
+    x = (x | (x << 2)) & 0x1249_2492_4924_9249;
+    x
+}
+
+fn compact_bits(code: u64) -> u32 {
+    let mut x = code & 0x1249_2492_4924_9249;
+    x = (x | (x >> 2)) & 0x10c3_0c30_c30c_30c3;
+    x = (x | (x >> 4)) & 0x100f_00f0_0f00_f00f;
+    x = (x | (x >> 8)) & 0x001f_0000_ff00_00ff;
+    x = (x | (x >> 16)) & 0x001f_0000_0000_ffff;
+    x = (x | (x >> 32)) & MORTON_MAX_COORD as u64;
+    x as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Bit-by-bit reference interleave.
+    fn naive_encode(x: u32, y: u32, z: u32) -> u64 {
+        (0..21).fold(0, |code, i| {
+            let bits = (x >> i & 1) as u64 | ((y >> i & 1) as u64) << 1 | ((z >> i & 1) as u64) << 2;
+            code | bits << (3 * i)
+        })
+    }
+
+    #[test]
+    fn axes_interleave_x_first() {
+        assert_eq!(morton_encode(1, 0, 0), 1);
+        assert_eq!(morton_encode(0, 1, 0), 2);
+        assert_eq!(morton_encode(0, 0, 1), 4);
+        assert_eq!(morton_encode(3, 0, 0), 0b1001);
+    }
+
+    #[test]
+    fn round_trip_at_the_21_bit_edges() {
+        let max = MORTON_MAX_COORD;
+        for (x, y, z) in [(0, 0, 0), (max, max, max), (max, 0, 1), (1 << 20, (1 << 20) - 1, 12345), (7, 8, 9)] {
+            assert_eq!(morton_decode(morton_encode(x, y, z)), vec![x, y, z]);
+            assert_eq!(morton_encode(x, y, z), naive_encode(x, y, z));
+        }
+        assert_eq!(morton_encode(max, max, max), (1 << 63) - 1);
+
+        // Bits above the 21st are dropped.
+        assert_eq!(morton_encode(max + 1, 1 << 21 | 5, u32::MAX), morton_encode(0, 5, max));
+        assert_eq!(morton_decode(u64::MAX), vec![max, max, max]);
+    }
+}
@@ -0,0 +1,217 @@
+use wasm_bindgen::prelude::*;
+use crate::math::{self, Matrix3, Point3, Vector3};
+use crate::{point3, Ray, AABB};
+
+/// Oriented bounding box. The orientation is a rotation matrix whose columns
+/// are the box's local x, y and z axes in world space.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct Obb {
+    center: Point3,
+    half_extents: Vector3,
+    orientation: Matrix3,
+}
+
+#[wasm_bindgen]
+impl Obb {
+    /// `orientation` is 9 floats in column-major order. Returns `None` unless
+    /// `center` and `half_extents` hold 3 floats and `orientation` holds 9.
+    pub fn new(center: &[f32], half_extents: &[f32], orientation: &[f32]) -> Option<Obb> {
+        if center.len() != 3 || half_extents.len() != 3 || orientation.len() != 9 {
+            return None;
+        }
+        Some(Obb {
+            center: point3(center),
+            half_extents: math::to_vector(point3(half_extents)),
+            orientation: math::matrix3(orientation),
+        })
+    }
+
+    /// Smallest axis-aligned box enclosing this one.
+    pub fn to_aabb(&self) -> AABB {
+        let extents = self.orientation.abs() * self.half_extents;
+        AABB {
+            min: self.center - extents,
+            max: self.center + extents,
+        }
+    }
+
+    /// Separating axis test over the 15 candidate axes: the three face axes
+    /// of each box and the nine pairwise edge cross products. Touching boxes
+    /// count as intersecting.
+    pub fn intersects_obb(&self, other: &Obb) -> bool {
+        // Padding the absolute rotation terms keeps near-parallel edge pairs,
+        // whose cross products are close to zero, from reporting a bogus
+        // separation.
+        const EPSILON: f32 = 1e-6;
+
+        let a = std::array::from_fn::<_, 3, _>(|i| self.axis(i));
+        let b = std::array::from_fn::<_, 3, _>(|i| other.axis(i));
+        let (ea, eb) = (self.half_extents, other.half_extents);
+
+        // `other` in the frame of `self`: its axes as rotation `r`, its
+        // center as offset `t`.
+        let r: [[f32; 3]; 3] = std::array::from_fn(|i| std::array::from_fn(|j| math::dot(a[i], b[j])));
+        let abs_r = r.map(|row| row.map(|v| v.abs() + EPSILON));
+        let offset = other.center - self.center;
+        let t: [f32; 3] = std::array::from_fn(|i| math::dot(offset, a[i]));
+
+        for i in 0..3 {
+            let rb = eb[0] * abs_r[i][0] + eb[1] * abs_r[i][1] + eb[2] * abs_r[i][2];
+            if t[i].abs() > ea[i] + rb {
+                return false;
+            }
+        }
+
+        for j in 0..3 {
+            let ra = ea[0] * abs_r[0][j] + ea[1] * abs_r[1][j] + ea[2] * abs_r[2][j];
+            let tj = t[0] * r[0][j] + t[1] * r[1][j] + t[2] * r[2][j];
+            if tj.abs() > ra + eb[j] {
+                return false;
+            }
+        }
+
+        for i in 0..3 {
+            let (i1, i2) = ((i + 1) % 3, (i + 2) % 3);
+            for j in 0..3 {
+                let (j1, j2) = ((j + 1) % 3, (j + 2) % 3);
+                let ra = ea[i1] * abs_r[i2][j] + ea[i2] * abs_r[i1][j];
+                let rb = eb[j1] * abs_r[i][j2] + eb[j2] * abs_r[i][j1];
+                if (t[i2] * r[i1][j] - t[i1] * r[i2][j]).abs() > ra + rb {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    pub fn intersects_aabb(&self, aabb: &AABB) -> bool {
+        let obb = Obb {
+            center: math::center(aabb.min, aabb.max),
+            half_extents: (aabb.max - aabb.min) * 0.5,
+            orientation: math::matrix3(&[1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]),
+        };
+        self.intersects_obb(&obb)
+    }
+}
+
+impl Obb {
+    // World-space direction of local axis `i`.
+    fn axis(&self, i: usize) -> Vector3 {
+        let mut unit = math::zero();
+        unit[i] = 1.0;
+        self.orientation * unit
+    }
+}
+
+#[wasm_bindgen]
+impl Ray {
+    /// Entry distance into `obb`, found by running the slab test in the box's
+    /// local frame. A ray starting inside reports `0.0`.
+    pub fn intersects_obb(&self, obb: &Obb) -> Option<f32> {
+        let inverse = obb.orientation.transpose();
+        let local = Ray {
+            origin: math::to_point(inverse * (self.origin - obb.center)),
+            direction: inverse * self.direction,
+        };
+        let bounds = AABB {
+            min: math::to_point(-obb.half_extents),
+            max: math::to_point(obb.half_extents),
+        };
+        local.distance_to_aabb(&bounds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Rotation by 45 degrees about z, column-major.
+    fn rotated_z() -> [f32; 9] {
+        let h = std::f32::consts::FRAC_1_SQRT_2;
+        [h, h, 0.0, -h, h, 0.0, 0.0, 0.0, 1.0]
+    }
+
+    #[test]
+    fn to_aabb_bounds_the_rotated_box() {
+        let obb = Obb::new(&[1.0, 2.0, 3.0], &[1.0, 1.0, 0.5], &rotated_z()).unwrap();
+        let aabb = obb.to_aabb();
+        let reach = std::f32::consts::SQRT_2;
+        assert!((aabb.min_x() - (1.0 - reach)).abs() < 1e-6);
+        assert!((aabb.max_y() - (2.0 + reach)).abs() < 1e-6);
+        assert_eq!((aabb.min_z(), aabb.max_z()), (2.5, 3.5));
+    }
+
+    #[test]
+    fn an_unrotated_box_matches_the_aabb() {
+        let identity = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        let obb = Obb::new(&[1.0, 1.0, 1.0], &[1.0, 2.0, 3.0], &identity).unwrap();
+        let aabb = AABB::new(0.0, -1.0, -2.0, 2.0, 3.0, 4.0);
+        assert_eq!(obb.to_aabb(), aabb);
+
+        let rays = [
+            Ray::new(-5.0, 0.0, 0.0, 1.0, 0.2, 0.1),
+            Ray::new(1.0, 10.0, 1.0, 0.0, -1.0, 0.0),
+            Ray::new(1.0, 1.0, 1.0, 0.0, 0.0, 1.0),
+            Ray::new(5.0, 5.0, 5.0, 1.0, 1.0, 1.0),
+        ];
+        for ray in rays {
+            assert_eq!(ray.intersects_obb(&obb), ray.distance_to_aabb(&aabb));
+        }
+    }
+
+    #[test]
+    fn rays_hit_the_rotated_box_where_it_turned_to() {
+        let obb = Obb::new(&[0.0, 0.0, 0.0], &[1.0, 1.0, 1.0], &rotated_z()).unwrap();
+        let t = Ray::new(-5.0, 0.0, 0.0, 1.0, 0.0, 0.0).intersects_obb(&obb).unwrap();
+        assert!((t - (5.0 - std::f32::consts::SQRT_2)).abs() < 1e-5);
+
+        // 1.3 clears the unrotated box but not the diamond; 1.5 clears both.
+        assert!(Ray::new(-5.0, 1.3, 0.0, 1.0, 0.0, 0.0).intersects_obb(&obb).is_some());
+        assert_eq!(Ray::new(-5.0, 1.5, 0.0, 1.0, 0.0, 0.0).intersects_obb(&obb), None);
+        // A corner of the unrotated box lies outside the diamond.
+        assert_eq!(Ray::new(0.95, 0.95, -5.0, 0.0, 0.0, 1.0).intersects_obb(&obb), None);
+    }
+
+    #[test]
+    fn boxes_separated_along_a_face_axis_or_overlapping() {
+        let obb = Obb::new(&[0.0, 0.0, 0.0], &[1.0, 1.0, 1.0], &rotated_z()).unwrap();
+        // The diamond's tip is at x = sqrt(2).
+        assert!(obb.intersects_aabb(&AABB::new(1.3, -0.5, -0.5, 2.3, 0.5, 0.5)));
+        assert!(!obb.intersects_aabb(&AABB::new(1.5, -0.5, -0.5, 2.5, 0.5, 0.5)));
+        assert!(!obb.intersects_aabb(&AABB::new(-0.5, -0.5, 1.1, 0.5, 0.5, 2.0)));
+
+        let identity = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        let cube = |x: f32| Obb::new(&[x, 0.0, 0.0], &[1.0, 1.0, 1.0], &identity).unwrap();
+        assert!(obb.intersects_obb(&cube(2.4)));
+        assert!(cube(2.4).intersects_obb(&obb));
+        assert!(!obb.intersects_obb(&cube(2.5)));
+        assert!(cube(0.0).intersects_obb(&cube(2.0)));
+    }
+
+    #[test]
+    fn crossed_edges_are_separated_only_by_their_cross_product() {
+        // `obb` has an edge along z at (0, sqrt(2)); `other`, turned about x,
+        // has one along x at y = center - sqrt(2). The gap between the edges
+        // is along y = z cross x, and every face axis still overlaps.
+        let h = std::f32::consts::FRAC_1_SQRT_2;
+        let rotated_x = [1.0, 0.0, 0.0, 0.0, h, h, 0.0, -h, h];
+        let obb = Obb::new(&[0.0, 0.0, 0.0], &[1.0, 1.0, 1.0], &rotated_z()).unwrap();
+        let other = |y: f32| Obb::new(&[0.0, y, 0.0], &[1.0, 1.0, 1.0], &rotated_x).unwrap();
+
+        let touching = 2.0 * std::f32::consts::SQRT_2;
+        assert!(!obb.intersects_obb(&other(touching + 0.1)));
+        assert!(!other(touching + 0.1).intersects_obb(&obb));
+        assert!(obb.intersects_obb(&other(touching - 0.1)));
+    }
+
+    #[test]
+    fn new_rejects_wrongly_sized_inputs() {
+        let identity = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        assert!(Obb::new(&[0.0, 0.0, 0.0], &[1.0, 1.0, 1.0], &identity[..6]).is_none());
+        assert!(Obb::new(&[0.0, 0.0], &[1.0, 1.0, 1.0], &identity).is_none());
+        assert!(Obb::new(&[0.0, 0.0, 0.0], &[1.0, 1.0, 1.0, 1.0], &identity).is_none());
+        assert!(Obb::new(&[0.0, 0.0, 0.0], &[1.0, 1.0, 1.0], &identity).is_some());
+    }
+}
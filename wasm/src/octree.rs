@@ -0,0 +1,149 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{Ray, AABB};
+
+/// Loose spatial index over `(id, AABB)` pairs. Each item lives in the
+/// deepest node that fully contains it; items outside the root bounds stay in
+/// the root.
+#[wasm_bindgen]
+pub struct Octree {
+    root: OctreeNode,
+    max_depth: u32,
+    max_items: usize,
+}
+
+struct OctreeNode {
+    bounds: AABB,
+    items: Vec<(u32, AABB)>,
+    children: Option<Box<[OctreeNode; 8]>>,
+}
+
+#[wasm_bindgen]
+impl Octree {
+    /// `max_items` is how many items a leaf holds before it subdivides, and
+    /// `max_depth` caps how many times that can happen.
+    #[wasm_bindgen(constructor)]
+    pub fn new(bounds: &AABB, max_depth: u32, max_items: u32) -> Octree {
+        Octree {
+            root: OctreeNode::new(*bounds),
+            max_depth,
+            max_items: max_items as usize,
+        }
+    }
+
+    pub fn insert(&mut self, id: u32, aabb: &AABB) {
+        self.root.insert(id, *aabb, 0, self.max_depth, self.max_items);
+    }
+
+    /// Ids of all items whose box intersects `region`.
+    pub fn query_aabb(&self, region: &AABB) -> Vec<u32> {
+        let mut ids = Vec::new();
+        self.root.query_aabb(region, &mut ids);
+        ids
+    }
+
+    /// Ids of all items hit by `ray`, nearest entry first.
+    pub fn query_ray(&self, ray: &Ray) -> Vec<u32> {
+        let mut hits = Vec::new();
+        self.root.query_ray(ray, &mut hits);
+        hits.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        hits.into_iter().map(|(_, id)| id).collect()
+    }
+}
+
+impl OctreeNode {
+    fn new(bounds: AABB) -> OctreeNode {
+        OctreeNode {
+            bounds,
+            items: Vec::new(),
+            children: None,
+        }
+    }
+
+    fn insert(&mut self, id: u32, aabb: AABB, depth: u32, max_depth: u32, max_items: usize) {
+        if let Some(children) = &mut self.children {
+            if let Some(child) = children.iter_mut().find(|child| child.bounds.contains_aabb(&aabb)) {
+                child.insert(id, aabb, depth + 1, max_depth, max_items);
+                return;
+            }
+        }
+
+        self.items.push((id, aabb));
+
+        if self.children.is_none() && self.items.len() > max_items && depth < max_depth {
+            self.subdivide(depth, max_depth, max_items);
+        }
+    }
+
+    fn subdivide(&mut self, depth: u32, max_depth: u32, max_items: usize) {
+        self.children = Some(Box::new(self.bounds.octant_boxes().map(OctreeNode::new)));
+
+        for (id, aabb) in std::mem::take(&mut self.items) {
+            self.insert(id, aabb, depth, max_depth, max_items);
+        }
+    }
+
+    fn query_aabb(&self, region: &AABB, ids: &mut Vec<u32>) {
+        ids.extend(self.items.iter().filter(|(_, aabb)| aabb.intersects(region)).map(|(id, _)| *id));
+
+        if let Some(children) = &self.children {
+            for child in children.iter().filter(|child| child.bounds.intersects(region)) {
+                child.query_aabb(region, ids);
+            }
+        }
+    }
+
+    fn query_ray(&self, ray: &Ray, hits: &mut Vec<(f32, u32)>) {
+        hits.extend(self.items.iter().filter_map(|(id, aabb)| ray.distance_to_aabb(aabb).map(|t| (t, *id))));
+
+        if let Some(children) = &self.children {
+            for child in children.iter().filter(|child| ray.intersects_aabb(&child.bounds)) {
+                child.query_ray(ray, hits);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::random_boxes;
+
+    #[test]
+    fn queries_match_brute_force() {
+        // Spans past the root bounds so some items stay in the root.
+        let boxes = random_boxes(7, 2000, 110.0, 8.0);
+        let mut octree = Octree::new(&AABB::new(-50.0, -50.0, -50.0, 50.0, 50.0, 50.0), 6, 4);
+        for (id, aabb) in boxes.iter().enumerate() {
+            octree.insert(id as u32, aabb);
+        }
+
+        let regions = random_boxes(8, 100, 100.0, 20.0);
+        let rays = random_boxes(9, 100, 100.0, 1.0);
+        let mut ray_hits = 0;
+        for (region, probe) in regions.iter().zip(&rays) {
+            let mut got = octree.query_aabb(region);
+            got.sort_unstable();
+            let want: Vec<u32> = (0..boxes.len() as u32).filter(|&id| boxes[id as usize].intersects(region)).collect();
+            assert_eq!(got, want);
+
+            let ray = Ray::new(
+                probe.min.x,
+                probe.min.y,
+                probe.min.z,
+                probe.max.x - probe.min.x - 0.5,
+                probe.max.y - probe.min.y - 0.5,
+                probe.max.z - probe.min.z - 0.5,
+            );
+            let mut want: Vec<(f32, u32)> = boxes
+                .iter()
+                .enumerate()
+                .filter_map(|(id, aabb)| ray.distance_to_aabb(aabb).map(|t| (t, id as u32)))
+                .collect();
+            want.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            ray_hits += want.len();
+            assert_eq!(octree.query_ray(&ray), want.into_iter().map(|(_, id)| id).collect::<Vec<_>>());
+        }
+        assert!(ray_hits > 100, "{ray_hits}");
+    }
+}
@@ -0,0 +1,137 @@
+use wasm_bindgen::prelude::*;
+use crate::math::{self, Point3, Vector3};
+use crate::{Ray, AABB};
+
+/// Plane `normal . p + d = 0`, with the normal kept at unit length.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct Plane {
+    normal: Vector3,
+    d: f32,
+}
+
+#[wasm_bindgen]
+impl Plane {
+    /// Normalizes `(nx, ny, nz)` and scales `d` to match. A zero normal
+    /// can't be normalized and is kept as is, giving a degenerate plane whose
+    /// signed distance is `d` everywhere: every box is in front of it, behind
+    /// it or (for `d = 0`) touching it.
+    #[wasm_bindgen(constructor)]
+    pub fn new(nx: f32, ny: f32, nz: f32, d: f32) -> Plane {
+        let normal = math::vector(nx, ny, nz);
+        let length = math::length(normal);
+        if length == 0.0 {
+            return Plane { normal, d };
+        }
+
+        Plane {
+            normal: normal / length,
+            d: d / length,
+        }
+    }
+
+    /// Plane through `(px, py, pz)` facing `(nx, ny, nz)`. A zero normal gives
+    /// the degenerate plane every box touches.
+    pub fn from_point_normal(px: f32, py: f32, pz: f32, nx: f32, ny: f32, nz: f32) -> Plane {
+        let normal = math::vector(nx, ny, nz);
+        Plane::new(normal.x, normal.y, normal.z, -math::dot(normal, math::vector(px, py, pz)))
+    }
+
+    pub fn signed_distance(&self, x: f32, y: f32, z: f32) -> f32 {
+        math::dot(self.normal, math::vector(x, y, z)) + self.d
+    }
+
+    /// Returns 1 when `aabb` is entirely in front of the plane, -1 when it is
+    /// entirely behind, and 0 when it straddles or touches it.
+    pub fn classify_aabb(&self, aabb: &AABB) -> i32 {
+        let center = math::center(aabb.min, aabb.max);
+        let half = (aabb.max - aabb.min) * 0.5;
+        let radius = math::dot(half, self.normal.abs());
+        let distance = self.signed_distance(center.x, center.y, center.z);
+
+        if distance > radius {
+            1
+        } else if distance < -radius {
+            -1
+        } else {
+            0
+        }
+    }
+}
+
+impl Plane {
+    // Point shared by three planes, non-finite when any two are parallel.
+    pub(crate) fn meet(a: &Plane, b: &Plane, c: &Plane) -> Point3 {
+        let bc = math::cross(b.normal, c.normal);
+        let ca = math::cross(c.normal, a.normal);
+        let ab = math::cross(a.normal, b.normal);
+        math::to_point((bc * a.d + ca * b.d + ab * c.d) * (-1.0 / math::dot(a.normal, bc)))
+    }
+}
+
+#[wasm_bindgen]
+impl Ray {
+    /// Distance along the ray to `plane`, or `None` when the ray is parallel
+    /// to it or points away from it.
+    pub fn intersects_plane(&self, plane: &Plane) -> Option<f32> {
+        let denominator = math::dot(plane.normal, self.direction);
+        if denominator.abs() <= f32::EPSILON {
+            return None;
+        }
+
+        let t = -(math::dot(plane.normal, math::to_vector(self.origin)) + plane.d) / denominator;
+        if t >= 0.0 {
+            Some(t)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signed_distance_uses_the_normalized_plane() {
+        // y = 1, given with an unnormalized normal.
+        let plane = Plane::new(0.0, 2.0, 0.0, -2.0);
+        assert_eq!(plane.signed_distance(0.0, 3.0, 0.0), 2.0);
+        assert_eq!(plane.signed_distance(5.0, 0.0, -4.0), -1.0);
+        let through = Plane::from_point_normal(0.0, 1.0, 0.0, 0.0, 3.0, 0.0);
+        assert_eq!(through.signed_distance(5.0, 3.0, 1.0), 2.0);
+    }
+
+    #[test]
+    fn classify_aabb_reports_each_side() {
+        let plane = Plane::new(0.0, 2.0, 0.0, -2.0);
+        assert_eq!(plane.classify_aabb(&AABB::new(0.0, 2.0, 0.0, 1.0, 3.0, 1.0)), 1);
+        assert_eq!(plane.classify_aabb(&AABB::new(0.0, -2.0, 0.0, 1.0, 0.0, 1.0)), -1);
+        assert_eq!(plane.classify_aabb(&AABB::new(0.0, 0.0, 0.0, 1.0, 3.0, 1.0)), 0);
+        // Touching from either side counts as straddling.
+        assert_eq!(plane.classify_aabb(&AABB::new(0.0, 1.0, 0.0, 1.0, 2.0, 1.0)), 0);
+        assert_eq!(plane.classify_aabb(&AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0)), 0);
+    }
+
+    #[test]
+    fn rays_parallel_to_or_leaving_the_plane_miss() {
+        let plane = Plane::new(0.0, 2.0, 0.0, -2.0);
+        assert_eq!(Ray::new(0.0, 5.0, 0.0, 0.0, -1.0, 0.0).intersects_plane(&plane), Some(4.0));
+        assert_eq!(Ray::new(0.0, -5.0, 0.0, 0.0, 1.0, 0.0).intersects_plane(&plane), Some(6.0));
+        assert_eq!(Ray::new(0.0, 5.0, 0.0, 0.0, 1.0, 0.0).intersects_plane(&plane), None);
+        assert_eq!(Ray::new(0.0, 5.0, 0.0, 1.0, 0.0, 0.0).intersects_plane(&plane), None);
+        assert_eq!(Ray::new(0.0, 1.0, 0.0, 1.0, 0.0, 0.0).intersects_plane(&plane), None);
+    }
+
+    #[test]
+    fn a_zero_normal_gives_a_finite_degenerate_plane() {
+        let aabb = AABB::new(-1.0, -1.0, -1.0, 1.0, 1.0, 1.0);
+        let front = Plane::new(0.0, 0.0, 0.0, 2.0);
+        assert_eq!(front.signed_distance(7.0, -3.0, 1.0), 2.0);
+        assert_eq!(front.classify_aabb(&aabb), 1);
+        assert_eq!(Plane::new(0.0, 0.0, 0.0, -2.0).classify_aabb(&aabb), -1);
+        assert_eq!(Plane::new(0.0, 0.0, 0.0, 0.0).classify_aabb(&aabb), 0);
+        assert_eq!(Plane::from_point_normal(1.0, 2.0, 3.0, 0.0, 0.0, 0.0).classify_aabb(&aabb), 0);
+        assert_eq!(Ray::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0).intersects_plane(&front), None);
+    }
+}
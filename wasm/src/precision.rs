@@ -0,0 +1,237 @@
+use std::ops::Index;
+
+use nalgebra as na;
+
+use crate::{entry_face, ray_slab, Ray, AABB};
+
+// Float types the shared geometry helpers are written for.
+pub(crate) trait Scalar: na::RealField + Copy {
+    const INFINITY: Self;
+}
+
+impl Scalar for f32 {
+    const INFINITY: f32 = f32::INFINITY;
+}
+
+impl Scalar for f64 {
+    const INFINITY: f64 = f64::INFINITY;
+}
+
+/// Double-precision counterpart of [`AABB`] for native callers working far
+/// from the origin, where `f32` coordinates are too coarse. Not exported to JS.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AABBd {
+    min: na::Point3<f64>,
+    max: na::Point3<f64>,
+}
+
+impl AABBd {
+    pub fn new(min_x: f64, min_y: f64, min_z: f64, max_x: f64, max_y: f64, max_z: f64) -> AABBd {
+        AABBd {
+            min: na::Point3::new(min_x, min_y, min_z),
+            max: na::Point3::new(max_x, max_y, max_z),
+        }
+    }
+
+    pub fn from_center_extents(cx: f64, cy: f64, cz: f64, hx: f64, hy: f64, hz: f64) -> AABBd {
+        AABBd {
+            min: na::Point3::new(cx - hx, cy - hy, cz - hz),
+            max: na::Point3::new(cx + hx, cy + hy, cz + hz),
+        }
+    }
+
+    /// Inverted box (min = +inf, max = -inf) that acts as the identity for `union`.
+    pub fn empty() -> AABBd {
+        AABBd {
+            min: na::Point3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+            max: na::Point3::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+        }
+    }
+
+    pub fn min(&self) -> na::Point3<f64> {
+        self.min
+    }
+
+    pub fn max(&self) -> na::Point3<f64> {
+        self.max
+    }
+
+    pub fn center(&self) -> na::Point3<f64> {
+        na::center(&self.min, &self.max)
+    }
+
+    pub fn half_extents(&self) -> na::Vector3<f64> {
+        (self.max - self.min) * 0.5
+    }
+
+    /// Whether `min <= max` on every axis.
+    pub fn is_valid(&self) -> bool {
+        self.min.x <= self.max.x && self.min.y <= self.max.y && self.min.z <= self.max.z
+    }
+
+    /// Volume of the box, or 0 for an invalid box.
+    pub fn volume(&self) -> f64 {
+        if !self.is_valid() {
+            return 0.0;
+        }
+        let size = self.max - self.min;
+        size.x * size.y * size.z
+    }
+
+    /// Surface area of the box, or 0 for an invalid box.
+    pub fn surface_area(&self) -> f64 {
+        if !self.is_valid() {
+            return 0.0;
+        }
+        let size = self.max - self.min;
+        2.0 * (size.x * size.y + size.y * size.z + size.x * size.z)
+    }
+
+    pub fn intersects(&self, other: &AABBd) -> bool {
+        self.min.x <= other.max.x && self.max.x >= other.min.x &&
+        self.min.y <= other.max.y && self.max.y >= other.min.y &&
+        self.min.z <= other.max.z && self.max.z >= other.min.z
+    }
+
+    pub fn contains_point(&self, x: f64, y: f64, z: f64) -> bool {
+        x >= self.min.x && x <= self.max.x &&
+        y >= self.min.y && y <= self.max.y &&
+        z >= self.min.z && z <= self.max.z
+    }
+
+    pub fn contains_aabb(&self, other: &AABBd) -> bool {
+        other.min.x >= self.min.x && other.max.x <= self.max.x &&
+        other.min.y >= self.min.y && other.max.y <= self.max.y &&
+        other.min.z >= self.min.z && other.max.z <= self.max.z
+    }
+
+    pub fn union(&self, other: &AABBd) -> AABBd {
+        AABBd {
+            min: self.min.inf(&other.min),
+            max: self.max.sup(&other.max),
+        }
+    }
+
+    pub fn merge(&mut self, other: &AABBd) {
+        self.min = self.min.inf(&other.min);
+        self.max = self.max.sup(&other.max);
+    }
+
+    pub fn expand_to_include(&mut self, x: f64, y: f64, z: f64) {
+        let point = na::Point3::new(x, y, z);
+        self.min = self.min.inf(&point);
+        self.max = self.max.sup(&point);
+    }
+
+    /// Closest point on or inside the box to the query point.
+    pub fn closest_point(&self, x: f64, y: f64, z: f64) -> na::Point3<f64> {
+        na::Point3::new(x, y, z).sup(&self.min).inf(&self.max)
+    }
+
+    /// Euclidean distance from the query point to the box, 0 when inside.
+    pub fn distance_to_point(&self, x: f64, y: f64, z: f64) -> f64 {
+        na::distance(&na::Point3::new(x, y, z), &self.closest_point(x, y, z))
+    }
+}
+
+impl From<AABB> for AABBd {
+    fn from(aabb: AABB) -> AABBd {
+        AABBd { min: widen(&aabb.min).into(), max: widen(&aabb.max).into() }
+    }
+}
+
+/// Double-precision counterpart of [`Ray`]. The direction is normalized on
+/// construction.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rayd {
+    origin: na::Point3<f64>,
+    direction: na::Vector3<f64>,
+}
+
+impl Rayd {
+    pub fn new(origin_x: f64, origin_y: f64, origin_z: f64, dir_x: f64, dir_y: f64, dir_z: f64) -> Rayd {
+        Rayd {
+            origin: na::Point3::new(origin_x, origin_y, origin_z),
+            direction: na::Vector3::new(dir_x, dir_y, dir_z).normalize(),
+        }
+    }
+
+    pub fn origin(&self) -> na::Point3<f64> {
+        self.origin
+    }
+
+    pub fn direction(&self) -> na::Vector3<f64> {
+        self.direction
+    }
+
+    /// Point at distance `t` along the ray.
+    pub fn at(&self, t: f64) -> na::Point3<f64> {
+        self.origin + self.direction * t
+    }
+
+    pub fn intersects_aabb(&self, aabb: &AABBd) -> bool {
+        self.slab_entry(aabb).is_some()
+    }
+
+    /// Distance along the ray to the entry point of `aabb`, or `None` on a miss.
+    /// A ray starting inside the box reports `0.0`.
+    pub fn distance_to_aabb(&self, aabb: &AABBd) -> Option<f64> {
+        self.slab_entry(aabb).map(|(tmin, _, _)| tmin.max(0.0))
+    }
+
+    /// Same face numbering as [`Ray::intersect_aabb_face`].
+    pub fn intersect_aabb_face(&self, aabb: &AABBd) -> Option<(f64, i32)> {
+        let (tmin, _, axis) = self.slab_entry(aabb)?;
+        Some(entry_face(tmin, axis, &self.direction))
+    }
+
+    fn slab_entry(&self, aabb: &AABBd) -> Option<(f64, f64, usize)> {
+        ray_slab(&self.origin, &self.direction, &aabb.min, &aabb.max)
+    }
+}
+
+impl From<Ray> for Rayd {
+    fn from(ray: Ray) -> Rayd {
+        Rayd { origin: widen(&ray.origin).into(), direction: widen(&ray.direction) }
+    }
+}
+
+// Works for either math backend's point or vector type.
+fn widen(v: &impl Index<usize, Output = f32>) -> na::Vector3<f64> {
+    na::Vector3::new(v[0] as f64, v[1] as f64, v[2] as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn f64_resolves_a_hit_that_f32_rounds_away() {
+        // A diagonal ray and a thin box 1e6 from the origin, where f32 spacing
+        // is 1/16: the origin rounds up by 0.0125 and both x faces of the box
+        // round to 1e6 + 10.3125, which the f32 ray crosses below the box.
+        let (x0, x1, y0, y1) = (1e6 + 10.31, 1e6 + 10.32, 10.255, 10.27);
+        let aabbd = AABBd::new(x0, y0, -1.0, x1, y1, 1.0);
+        let rayd = Rayd::new(1e6 + 0.05, 0.0, 0.0, 1.0, 1.0, 0.0);
+        let t = rayd.distance_to_aabb(&aabbd).unwrap();
+        assert!((t - 10.26 * std::f64::consts::SQRT_2).abs() < 1e-6);
+        assert_eq!(rayd.intersect_aabb_face(&aabbd).map(|(_, face)| face), Some(0));
+
+        let aabb = AABB::new(x0 as f32, y0 as f32, -1.0, x1 as f32, y1 as f32, 1.0);
+        let ray = Ray::new((1e6 + 0.05) as f32, 0.0, 0.0, 1.0, 1.0, 0.0);
+        assert_eq!(ray.distance_to_aabb(&aabb), None);
+    }
+
+    #[test]
+    fn widened_f32_values_give_the_same_answer() {
+        let aabb = AABB::new(0.0, 0.0, 0.0, 1.0, 2.0, 3.0);
+        let ray = Ray::new(-4.0, 1.0, 1.0, 1.0, 0.0, 0.0);
+        let (aabbd, rayd) = (AABBd::from(aabb), Rayd::from(ray));
+        assert_eq!(aabbd.volume(), 6.0);
+        assert_eq!(rayd.distance_to_aabb(&aabbd), Some(4.0));
+        assert_eq!(rayd.intersect_aabb_face(&aabbd), Some((4.0, 0)));
+        assert_eq!(ray.intersect_aabb_face(&aabb), Some((4.0, 0)));
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::compression::{read_varint, write_varint};
+use crate::{math, Chunk};
+
+const REGION_VERSION: u8 = 1;
+
+/// Set of compressed chunks keyed by chunk coordinate, stored as one blob.
+/// Chunks stay compressed in memory and are only decoded on `get_chunk`.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct ChunkRegion {
+    chunks: BTreeMap<(i32, i32, i32), Vec<u8>>,
+}
+
+#[wasm_bindgen]
+impl ChunkRegion {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> ChunkRegion {
+        ChunkRegion::default()
+    }
+
+    /// Stores `chunk` at the given chunk coordinate, replacing any chunk there.
+    pub fn insert(&mut self, cx: i32, cy: i32, cz: i32, chunk: &Chunk) {
+        self.chunks.insert((cx, cy, cz), chunk.to_compressed());
+    }
+
+    pub fn remove(&mut self, cx: i32, cy: i32, cz: i32) -> bool {
+        self.chunks.remove(&(cx, cy, cz)).is_some()
+    }
+
+    /// Decodes the chunk at the given chunk coordinate, placing its origin at
+    /// the coordinate times the chunk's world-space extent, so a downsampled
+    /// chunk covers the same ground as the full-resolution one it came from.
+    pub fn get_chunk(&self, cx: i32, cy: i32, cz: i32) -> Option<Chunk> {
+        let mut chunk = Chunk::from_compressed(self.chunks.get(&(cx, cy, cz))?)?;
+        let extent = chunk.size() as f32 * chunk.voxel_size();
+        chunk.set_origin(math::point(cx as f32 * extent, cy as f32 * extent, cz as f32 * extent));
+        Some(chunk)
+    }
+
+    /// Chunk coordinates as `cx, cy, cz` per chunk, in ascending order.
+    pub fn coords(&self) -> Vec<i32> {
+        self.chunks.keys().flat_map(|&(x, y, z)| [x, y, z]).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Packs the region as:
+    ///
+    /// - `u8` format version (currently 1)
+    /// - varint chunk count
+    /// - per chunk, zigzag varint `cx, cy, cz`, then varint offset and length
+    ///   of its data, with offsets counted from the end of this table
+    /// - the `to_compressed` bytes of each chunk
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = vec![REGION_VERSION];
+        write_varint(&mut out, self.chunks.len() as u32);
+
+        let mut offset = 0;
+        for (&(x, y, z), data) in &self.chunks {
+            for coord in [x, y, z] {
+                write_varint(&mut out, zigzag(coord));
+            }
+            write_varint(&mut out, offset);
+            write_varint(&mut out, data.len() as u32);
+            offset += data.len() as u32;
+        }
+
+        for data in self.chunks.values() {
+            out.extend_from_slice(data);
+        }
+        out
+    }
+
+    /// Reads the output of `serialize`, returning `None` if the header or
+    /// offset table is malformed. Chunk data is only validated when a chunk
+    /// is decoded.
+    pub fn deserialize(bytes: &[u8]) -> Option<ChunkRegion> {
+        let (&version, mut rest) = bytes.split_first()?;
+        if version != REGION_VERSION {
+            return None;
+        }
+
+        let count = read_varint(&mut rest)?;
+        let mut table = Vec::new();
+        for _ in 0..count {
+            let x = unzigzag(read_varint(&mut rest)?);
+            let y = unzigzag(read_varint(&mut rest)?);
+            let z = unzigzag(read_varint(&mut rest)?);
+            let offset = read_varint(&mut rest)? as usize;
+            let length = read_varint(&mut rest)? as usize;
+            table.push(((x, y, z), offset, length));
+        }
+
+        let mut chunks = BTreeMap::new();
+        for (coord, offset, length) in table {
+            let data = rest.get(offset..offset.checked_add(length)?)?;
+            chunks.insert(coord, data.to_vec());
+        }
+        Some(ChunkRegion { chunks })
+    }
+}
+
+fn zigzag(value: i32) -> u32 {
+    ((value << 1) ^ (value >> 31)) as u32
+}
+
+fn unzigzag(value: u32) -> i32 {
+    (value >> 1) as i32 ^ -((value & 1) as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::lcg;
+
+    #[test]
+    fn downsampled_chunks_keep_their_world_placement() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        chunk.fill(1);
+        let mut region = ChunkRegion::new();
+        region.insert(1, 0, 0, &chunk.downsample());
+        region.insert(0, 0, 1, &chunk);
+
+        let lod = region.get_chunk(1, 0, 0).unwrap().bounds();
+        assert_eq!((lod.min_x(), lod.max_x()), (32.0, 64.0));
+        let full = region.get_chunk(0, 0, 1).unwrap().bounds();
+        assert_eq!((full.min_z(), full.max_z()), (32.0, 64.0));
+    }
+
+    #[test]
+    fn sparse_and_dense_chunks_round_trip() {
+        let empty = ChunkRegion::new();
+        assert!(ChunkRegion::deserialize(&empty.serialize()).unwrap().is_empty());
+
+        let mut dense = Chunk::new(0.0, 0.0, 0.0);
+        let mut next = lcg(99);
+        for i in 0..dense.len() as i32 {
+            dense.set(i % 32, i / 32 % 32, i / 1024, (next() >> 28) as u16);
+        }
+        let mut sparse = Chunk::new(0.0, 0.0, 0.0);
+        sparse.set(3, 4, 5, 9);
+
+        let mut region = ChunkRegion::new();
+        region.insert(-1, 2, -300, &dense);
+        region.insert(0, 0, 0, &sparse);
+        region.insert(70000, -70000, 1, &Chunk::new(0.0, 0.0, 0.0));
+        let loaded = ChunkRegion::deserialize(&region.serialize()).unwrap();
+
+        assert_eq!(loaded.len(), 3);
+        assert_eq!(loaded.coords(), vec![-1, 2, -300, 0, 0, 0, 70000, -70000, 1]);
+        let chunk = loaded.get_chunk(-1, 2, -300).unwrap();
+        assert_eq!(chunk.voxels(), dense.voxels());
+        assert_eq!((chunk.bounds().min_x(), chunk.bounds().min_z()), (-32.0, -9600.0));
+        assert_eq!(loaded.get_chunk(0, 0, 0).unwrap().get(3, 4, 5), 9);
+        assert_eq!(loaded.get_chunk(0, 0, 0).unwrap().solid_count(), 1);
+        assert!(loaded.get_chunk(70000, -70000, 1).unwrap().is_empty());
+        assert!(loaded.get_chunk(1, 1, 1).is_none());
+    }
+
+    #[test]
+    fn corrupted_offset_table_is_rejected() {
+        let mut region = ChunkRegion::new();
+        region.insert(0, 0, 0, &Chunk::new(0.0, 0.0, 0.0));
+        region.insert(1, 0, 0, &Chunk::new(0.0, 0.0, 0.0));
+        let bytes = region.serialize();
+        assert!(ChunkRegion::deserialize(&bytes).is_some());
+
+        // Header: version, count, then per chunk three coords, offset, length.
+        let second_offset = 2 + 5 + 3;
+        assert_eq!(bytes[second_offset], bytes[2 + 4]);
+        let mut past_end = bytes.clone();
+        past_end[second_offset] = 100;
+        assert!(ChunkRegion::deserialize(&past_end).is_none());
+
+        let mut long = bytes.clone();
+        long[second_offset + 1] = 0x7f;
+        assert!(ChunkRegion::deserialize(&long).is_none());
+
+        assert!(ChunkRegion::deserialize(&bytes[..bytes.len() - 1]).is_none());
+        assert!(ChunkRegion::deserialize(&[REGION_VERSION, 5]).is_none());
+        assert!(ChunkRegion::deserialize(&[2]).is_none());
+        assert!(ChunkRegion::deserialize(&[]).is_none());
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::math::{self, Point3};
+use crate::{Chunk, AABB, CHUNK_SIZE};
+
+/// Chunk that stores only its non-air voxels, keyed by the same packed index
+/// `x + size * (y + size * z)` as [`Chunk`]. Each stored voxel costs several
+/// times what a dense cell does, so this pays off for chunks that are less
+/// than roughly a tenth solid, such as sky or sparse decoration; convert to
+/// a dense `Chunk` for anything fuller, and before meshing.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "SparseChunkData"))]
+pub struct SparseChunk {
+    origin: Point3,
+    size: u32,
+    voxel_size: f32,
+    voxels: HashMap<u32, u16>,
+}
+
+#[wasm_bindgen]
+impl SparseChunk {
+    /// Creates an all-air chunk whose minimum corner sits at the given world position.
+    #[wasm_bindgen(constructor)]
+    pub fn new(origin_x: f32, origin_y: f32, origin_z: f32) -> SparseChunk {
+        SparseChunk {
+            origin: math::point(origin_x, origin_y, origin_z),
+            size: CHUNK_SIZE,
+            voxel_size: 1.0,
+            voxels: HashMap::new(),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Voxel id at the given local coordinates, or 0 (air) when out of range
+    /// or not stored.
+    pub fn get(&self, x: i32, y: i32, z: i32) -> u16 {
+        self.index(x, y, z).and_then(|i| self.voxels.get(&i)).copied().unwrap_or(0)
+    }
+
+    /// Writes a voxel id, returning `false` when the coordinates are out of
+    /// range. Writing air removes the stored entry.
+    pub fn set(&mut self, x: i32, y: i32, z: i32, id: u16) -> bool {
+        let Some(i) = self.index(x, y, z) else {
+            return false;
+        };
+
+        if id == 0 {
+            self.voxels.remove(&i);
+        } else {
+            self.voxels.insert(i, id);
+        }
+        true
+    }
+
+    /// Sets every voxel to `id`. Filling with a solid id stores every cell, so
+    /// prefer a dense `Chunk` for that.
+    pub fn fill(&mut self, id: u16) {
+        self.voxels.clear();
+        if id != 0 {
+            self.voxels.extend((0..self.size * self.size * self.size).map(|i| (i, id)));
+        }
+    }
+
+    /// World-space box covered by the chunk.
+    pub fn bounds(&self) -> AABB {
+        let extent = math::splat(self.size as f32 * self.voxel_size);
+        AABB { min: self.origin, max: self.origin + extent }
+    }
+
+    /// Number of stored, i.e. non-air, voxels.
+    pub fn stored_count(&self) -> usize {
+        self.voxels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.voxels.is_empty()
+    }
+
+    pub fn to_dense(&self) -> Chunk {
+        let mut voxels = vec![0; (self.size * self.size * self.size) as usize];
+        for (&i, &id) in &self.voxels {
+            voxels[i as usize] = id;
+        }
+
+        let mut chunk = Chunk::from_voxels(self.origin, self.size, voxels);
+        chunk.set_voxel_size(self.voxel_size);
+        chunk
+    }
+
+    pub fn from_dense(chunk: &Chunk) -> SparseChunk {
+        let voxels = chunk
+            .voxels()
+            .iter()
+            .enumerate()
+            .filter(|&(_, &id)| id != 0)
+            .map(|(i, &id)| (i as u32, id))
+            .collect();
+
+        SparseChunk {
+            origin: chunk.bounds().min,
+            size: chunk.size(),
+            voxel_size: chunk.voxel_size(),
+            voxels,
+        }
+    }
+}
+
+impl SparseChunk {
+    fn index(&self, x: i32, y: i32, z: i32) -> Option<u32> {
+        let size = self.size as i32;
+        if (0..size).contains(&x) && (0..size).contains(&y) && (0..size).contains(&z) {
+            Some((x + size * (y + size * z)) as u32)
+        } else {
+            None
+        }
+    }
+}
+
+// Deserialized form of a `SparseChunk`, checked so every key indexes a cell
+// inside the chunk and no air is stored.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct SparseChunkData {
+    origin: Point3,
+    size: u32,
+    voxel_size: f32,
+    voxels: HashMap<u32, u16>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SparseChunkData> for SparseChunk {
+    type Error = String;
+
+    fn try_from(data: SparseChunkData) -> Result<SparseChunk, String> {
+        let volume = data.size.checked_pow(3).ok_or_else(|| format!("chunk size {} is too large", data.size))?;
+        if let Some((&index, _)) = data.voxels.iter().find(|&(&index, _)| index >= volume) {
+            return Err(format!("voxel index {index} is outside a chunk of size {}", data.size));
+        }
+        if data.voxels.values().any(|&id| id == 0) {
+            return Err("air voxels must not be stored".to_string());
+        }
+        if !(data.voxel_size.is_finite() && data.voxel_size > 0.0) {
+            return Err(format!("invalid voxel size {}", data.voxel_size));
+        }
+
+        Ok(SparseChunk {
+            origin: data.origin,
+            size: data.size,
+            voxel_size: data.voxel_size,
+            voxels: data.voxels,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_voxel_round_trips_through_dense() {
+        let mut sparse = SparseChunk::new(32.0, 0.0, -32.0);
+        assert!(sparse.is_empty());
+        assert!(sparse.set(3, 4, 5, 7));
+        assert!(!sparse.set(32, 0, 0, 1));
+        assert_eq!((sparse.stored_count(), sparse.get(3, 4, 5)), (1, 7));
+        assert_eq!((sparse.get(0, 0, 0), sparse.get(-1, 0, 0)), (0, 0));
+
+        let dense = sparse.to_dense();
+        assert_eq!((dense.get(3, 4, 5), dense.solid_count()), (7, 1));
+        assert_eq!((dense.bounds().min_x(), dense.bounds().max_z()), (32.0, 0.0));
+
+        let back = SparseChunk::from_dense(&dense);
+        assert_eq!((back.stored_count(), back.get(3, 4, 5), back.bounds().min_z()), (1, 7, -32.0));
+        sparse.set(3, 4, 5, 0);
+        assert!(sparse.is_empty());
+    }
+
+    #[test]
+    fn downsampled_chunks_keep_their_scale() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        chunk.fill(3);
+        let lod = SparseChunk::from_dense(&chunk.downsample());
+        assert_eq!((lod.size(), lod.bounds().max_x(), lod.to_dense().voxel_size()), (16, 32.0, 2.0));
+        assert_eq!(lod.stored_count(), 16 * 16 * 16);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rejects_keys_outside_the_chunk() {
+        let mut sparse = SparseChunk::new(0.0, 0.0, 0.0);
+        sparse.set(31, 31, 31, 4);
+        let json = serde_json::to_string(&sparse).unwrap();
+        assert_eq!(serde_json::from_str::<SparseChunk>(&json).unwrap().get(31, 31, 31), 4);
+
+        let outside = json.replace("\"32767\"", "\"32768\"");
+        assert_ne!(outside, json);
+        assert!(serde_json::from_str::<SparseChunk>(&outside).is_err());
+        let air = json.replace(":4}", ":0}");
+        assert_ne!(air, json);
+        assert!(serde_json::from_str::<SparseChunk>(&air).is_err());
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::{HashMap, HashSet};
+
+use wasm_bindgen::prelude::*;
+
+use crate::AABB;
+
+type Cell = (i32, i32, i32);
+
+// Most cells one object is registered in. Bigger or unbounded boxes go in the
+// overflow set instead, so inserting them doesn't walk millions of cells.
+const MAX_OBJECT_CELLS: u64 = 512;
+
+/// Uniform grid broadphase for boxes that move every frame. Each object is
+/// registered in every cell its box overlaps, except for boxes spanning more
+/// than a few hundred cells, which every query checks directly.
+#[wasm_bindgen]
+pub struct SpatialHashGrid {
+    cell_size: f32,
+    cells: HashMap<Cell, Vec<u32>>,
+    overflow: HashSet<u32>,
+    objects: HashMap<u32, AABB>,
+}
+
+#[wasm_bindgen]
+impl SpatialHashGrid {
+    #[wasm_bindgen(constructor)]
+    pub fn new(cell_size: f32) -> SpatialHashGrid {
+        SpatialHashGrid {
+            cell_size,
+            cells: HashMap::new(),
+            overflow: HashSet::new(),
+            objects: HashMap::new(),
+        }
+    }
+
+    /// Adds an object, replacing any existing entry with the same id.
+    pub fn insert(&mut self, id: u32, aabb: &AABB) {
+        self.remove(id);
+        if self.is_oversized(aabb) {
+            self.overflow.insert(id);
+        } else {
+            for cell in self.cells_overlapping(aabb) {
+                self.cells.entry(cell).or_default().push(id);
+            }
+        }
+        self.objects.insert(id, *aabb);
+    }
+
+    /// Removes an object, returning `false` if the id was not present.
+    pub fn remove(&mut self, id: u32) -> bool {
+        let Some(aabb) = self.objects.remove(&id) else {
+            return false;
+        };
+        if self.overflow.remove(&id) {
+            return true;
+        }
+
+        for cell in self.cells_overlapping(&aabb) {
+            if let Some(ids) = self.cells.get_mut(&cell) {
+                ids.retain(|&other| other != id);
+                if ids.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+        true
+    }
+
+    pub fn update(&mut self, id: u32, aabb: &AABB) {
+        self.insert(id, aabb);
+    }
+
+    /// Ids of objects whose box intersects `region`, in ascending order.
+    pub fn query(&self, region: &AABB) -> Vec<u32> {
+        let mut ids: Vec<u32> = if self.cell_count(region) > self.objects.len() as u64 {
+            // Scanning the objects directly is cheaper than walking a region
+            // that covers more cells than there are objects.
+            self.objects.keys().copied().collect()
+        } else {
+            let mut seen = HashSet::new();
+            self.cells_overlapping(region)
+                .filter_map(|cell| self.cells.get(&cell))
+                .flatten()
+                .chain(&self.overflow)
+                .copied()
+                .filter(|id| seen.insert(*id))
+                .collect()
+        };
+
+        ids.retain(|id| self.objects[id].intersects(region));
+        ids.sort_unstable();
+        ids
+    }
+}
+
+impl SpatialHashGrid {
+    fn cell_range(&self, aabb: &AABB) -> ([i32; 3], [i32; 3]) {
+        let cell = |value: f32| (value / self.cell_size).floor() as i32;
+        (
+            [cell(aabb.min.x), cell(aabb.min.y), cell(aabb.min.z)],
+            [cell(aabb.max.x), cell(aabb.max.y), cell(aabb.max.z)],
+        )
+    }
+
+    fn cell_count(&self, aabb: &AABB) -> u64 {
+        let (lo, hi) = self.cell_range(aabb);
+        let span = |axis: usize| (hi[axis] as i64 - lo[axis] as i64 + 1).max(0) as u64;
+        span(0).saturating_mul(span(1)).saturating_mul(span(2))
+    }
+
+    fn is_oversized(&self, aabb: &AABB) -> bool {
+        let finite = (0..3).all(|axis| aabb.min[axis].is_finite() && aabb.max[axis].is_finite());
+        !finite || self.cell_count(aabb) > MAX_OBJECT_CELLS
+    }
+
+    fn cells_overlapping(&self, aabb: &AABB) -> impl Iterator<Item = Cell> {
+        let (lo, hi) = self.cell_range(aabb);
+        (lo[2]..=hi[2]).flat_map(move |z| (lo[1]..=hi[1]).flat_map(move |y| (lo[0]..=hi[0]).map(move |x| (x, y, z))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn straddling_object_is_found_from_every_cell() {
+        let mut grid = SpatialHashGrid::new(4.0);
+        grid.insert(1, &AABB::new(3.0, 3.0, 3.0, 5.0, 5.0, 5.0));
+        grid.insert(2, &AABB::new(0.5, 0.5, 0.5, 1.0, 1.0, 1.0));
+
+        assert_eq!(grid.query(&AABB::new(3.5, 3.5, 3.5, 3.6, 3.6, 3.6)), vec![1]);
+        assert_eq!(grid.query(&AABB::new(4.5, 4.5, 4.5, 4.6, 4.6, 4.6)), vec![1]);
+        assert_eq!(grid.query(&AABB::new(0.0, 0.0, 0.0, 3.5, 3.5, 3.5)), vec![1, 2]);
+        assert!(grid.query(&AABB::new(6.0, 6.0, 6.0, 7.0, 7.0, 7.0)).is_empty());
+
+        grid.update(1, &AABB::new(20.0, 0.0, 0.0, 21.0, 1.0, 1.0));
+        assert!(grid.query(&AABB::new(4.5, 4.5, 4.5, 4.6, 4.6, 4.6)).is_empty());
+        assert!(grid.remove(1));
+        assert!(!grid.remove(1));
+        assert!(grid.cells.values().all(|ids| !ids.contains(&1)));
+    }
+
+    #[test]
+    fn oversized_boxes_skip_the_cells() {
+        let mut grid = SpatialHashGrid::new(1.0);
+        grid.insert(1, &AABB::new(0.0, 0.0, 0.0, 1000.0, 1.0, 1000.0));
+        let inf = f32::INFINITY;
+        grid.insert(2, &AABB::new(-inf, -inf, -inf, inf, inf, inf));
+        grid.insert(3, &AABB::new(0.2, 0.2, 0.2, 0.8, 0.8, 0.8));
+        assert_eq!(grid.overflow.len(), 2);
+        assert!(grid.cells.len() <= MAX_OBJECT_CELLS as usize);
+
+        assert_eq!(grid.query(&AABB::new(0.1, 0.1, 0.1, 0.3, 0.3, 0.3)), vec![1, 2, 3]);
+        assert_eq!(grid.query(&AABB::new(500.0, 0.5, 500.0, 501.0, 0.6, 501.0)), vec![1, 2]);
+        assert_eq!(grid.query(&AABB::new(-9.0, -9.0, -9.0, -8.0, -8.0, -8.0)), vec![2]);
+
+        assert!(grid.remove(2));
+        grid.update(1, &AABB::new(5.0, 5.0, 5.0, 6.0, 6.0, 6.0));
+        assert!(grid.overflow.is_empty());
+        assert_eq!(grid.query(&AABB::new(5.5, 5.5, 5.5, 5.6, 5.6, 5.6)), vec![1]);
+    }
+}
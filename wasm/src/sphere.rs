@@ -0,0 +1,95 @@
+use wasm_bindgen::prelude::*;
+use crate::math::{self, Point3};
+use crate::{Ray, AABB};
+
+#[wasm_bindgen]
+pub struct Sphere {
+    center: Point3,
+    radius: f32,
+}
+
+#[wasm_bindgen]
+impl Sphere {
+    #[wasm_bindgen(constructor)]
+    pub fn new(cx: f32, cy: f32, cz: f32, radius: f32) -> Sphere {
+        Sphere {
+            center: math::point(cx, cy, cz),
+            radius,
+        }
+    }
+
+    pub fn contains_point(&self, x: f32, y: f32, z: f32) -> bool {
+        math::distance_squared(self.center, math::point(x, y, z)) <= self.radius * self.radius
+    }
+
+    pub fn intersects_aabb(&self, aabb: &AABB) -> bool {
+        let closest = aabb.clamp_point(&self.center);
+        math::distance_squared(self.center, closest) <= self.radius * self.radius
+    }
+}
+
+#[wasm_bindgen]
+impl Ray {
+    /// Distance to the nearest hit on `sphere`, or `None` on a miss. Like
+    /// `distance_to_aabb`, a ray starting inside the sphere reports `0.0`.
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> Option<f32> {
+        let offset = self.origin - sphere.center;
+        let b = math::dot(offset, self.direction);
+        let c = math::length_squared(offset) - sphere.radius * sphere.radius;
+
+        if c <= 0.0 {
+            return Some(0.0);
+        }
+
+        let discriminant = b * b - c;
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        let t = -b - discriminant.sqrt();
+        if t >= 0.0 {
+            Some(t)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_on_the_surface_are_contained() {
+        let sphere = Sphere::new(0.0, 0.0, 0.0, 1.0);
+        assert!(sphere.contains_point(0.0, 0.0, 0.0));
+        assert!(sphere.contains_point(1.0, 0.0, 0.0));
+        assert!(!sphere.contains_point(1.0, 0.1, 0.0));
+    }
+
+    #[test]
+    fn intersects_aabb_uses_the_closest_point() {
+        let sphere = Sphere::new(0.0, 0.0, 0.0, 1.0);
+        assert!(sphere.intersects_aabb(&AABB::new(0.5, 0.5, 0.5, 2.0, 2.0, 2.0)));
+        // The corner (0.6, 0.6, 0.6) is just over 1 away.
+        assert!(!sphere.intersects_aabb(&AABB::new(0.6, 0.6, 0.6, 2.0, 2.0, 2.0)));
+        assert!(sphere.intersects_aabb(&AABB::new(1.0, -5.0, -5.0, 2.0, 5.0, 5.0)));
+        assert!(sphere.intersects_aabb(&AABB::new(-5.0, -5.0, -5.0, 5.0, 5.0, 5.0)));
+    }
+
+    #[test]
+    fn rays_hit_the_near_side() {
+        let sphere = Sphere::new(0.0, 0.0, 0.0, 1.0);
+        assert_eq!(Ray::new(-3.0, 0.0, 0.0, 1.0, 0.0, 0.0).intersects_sphere(&sphere), Some(2.0));
+        assert_eq!(Ray::new(-3.0, 1.0, 0.0, 1.0, 0.0, 0.0).intersects_sphere(&sphere), Some(3.0));
+        assert_eq!(Ray::new(-3.0, 2.0, 0.0, 1.0, 0.0, 0.0).intersects_sphere(&sphere), None);
+    }
+
+    #[test]
+    fn a_ray_inside_hits_at_zero_and_one_past_it_misses() {
+        let sphere = Sphere::new(0.0, 0.0, 0.0, 1.0);
+        assert_eq!(Ray::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0).intersects_sphere(&sphere), Some(0.0));
+        assert_eq!(Ray::new(0.5, 0.0, 0.0, -1.0, 0.0, 0.0).intersects_sphere(&sphere), Some(0.0));
+        assert_eq!(Ray::new(3.0, 0.0, 0.0, 1.0, 0.0, 0.0).intersects_sphere(&sphere), None);
+    }
+}
@@ -0,0 +1,147 @@
+use wasm_bindgen::prelude::*;
+use crate::math::{self, Vector3};
+use crate::AABB;
+
+/// First contact of a moving box against a static one.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct SweepHit {
+    pub(crate) time: f32,
+    pub(crate) normal: Vector3,
+}
+
+#[wasm_bindgen]
+impl SweepHit {
+    /// Fraction of the motion, in `[0, 1]`, at which the boxes first touch.
+    #[wasm_bindgen(getter)]
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Normal of the static box's face that was hit, or zero when the boxes
+    /// already overlapped.
+    #[wasm_bindgen(getter)]
+    pub fn normal(&self) -> Vec<f32> {
+        vec![self.normal.x, self.normal.y, self.normal.z]
+    }
+}
+
+#[wasm_bindgen]
+impl AABB {
+    /// Fraction of `velocity` this box can travel before touching `other`.
+    /// Boxes that already overlap report `0.0`; boxes that only touch are
+    /// blocked when moving into each other and free to slide or separate.
+    pub fn sweep(&self, velocity: &[f32], other: &AABB) -> Option<f32> {
+        self.sweep_hit(velocity, other).map(|hit| hit.time)
+    }
+
+    /// Like `sweep`, also reporting the normal of the face that was hit.
+    /// A `velocity` with fewer than 3 components never hits.
+    pub fn sweep_hit(&self, velocity: &[f32], other: &AABB) -> Option<SweepHit> {
+        let &[x, y, z, ..] = velocity else {
+            return None;
+        };
+        self.sweep_against(&math::vector(x, y, z), other)
+    }
+}
+
+impl AABB {
+    pub(crate) fn sweep_against(&self, velocity: &Vector3, other: &AABB) -> Option<SweepHit> {
+        let mut t_entry = f32::NEG_INFINITY;
+        let mut t_exit = f32::INFINITY;
+        let mut entry_axis = 0;
+
+        for axis in 0..3 {
+            let v = velocity[axis];
+            if v == 0.0 {
+                if self.max[axis] <= other.min[axis] || self.min[axis] >= other.max[axis] {
+                    return None;
+                }
+                continue;
+            }
+
+            let (enter, exit) = if v > 0.0 {
+                ((other.min[axis] - self.max[axis]) / v, (other.max[axis] - self.min[axis]) / v)
+            } else {
+                ((other.max[axis] - self.min[axis]) / v, (other.min[axis] - self.max[axis]) / v)
+            };
+
+            if enter > t_entry {
+                t_entry = enter;
+                entry_axis = axis;
+            }
+            t_exit = t_exit.min(exit);
+        }
+
+        if t_entry >= t_exit || t_exit <= 0.0 || t_entry > 1.0 {
+            return None;
+        }
+
+        if t_entry < 0.0 {
+            return Some(SweepHit { time: 0.0, normal: math::zero() });
+        }
+
+        let mut normal = math::zero();
+        normal[entry_axis] = -velocity[entry_axis].signum();
+        Some(SweepHit { time: t_entry, normal })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit_box() -> AABB {
+        AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0)
+    }
+
+    #[test]
+    fn moving_into_a_wall_stops_at_its_face() {
+        let wall = AABB::new(3.0, -5.0, -5.0, 4.0, 5.0, 5.0);
+        let hit = unit_box().sweep_hit(&[4.0, 0.0, 0.0], &wall).unwrap();
+        assert_eq!(hit.time(), 0.5);
+        assert_eq!(hit.normal(), vec![-1.0, 0.0, 0.0]);
+
+        let back = AABB::new(-6.0, 0.0, 0.0, -5.0, 1.0, 1.0).sweep_hit(&[-4.0, 0.0, 0.0], &unit_box());
+        assert_eq!(back.map(|hit| (hit.time(), hit.normal())), None);
+        let hit = AABB::new(3.0, 0.0, 0.0, 4.0, 1.0, 1.0).sweep_hit(&[-4.0, 0.0, 0.0], &unit_box()).unwrap();
+        assert_eq!((hit.time(), hit.normal()), (0.5, vec![1.0, 0.0, 0.0]));
+    }
+
+    #[test]
+    fn overlapping_boxes_hit_at_zero() {
+        let other = AABB::new(0.5, 0.5, 0.5, 2.0, 2.0, 2.0);
+        assert_eq!(unit_box().sweep(&[1.0, 0.0, 0.0], &other), Some(0.0));
+        assert_eq!(unit_box().sweep(&[0.0, 0.0, 0.0], &other), Some(0.0));
+        assert_eq!(unit_box().sweep_hit(&[-1.0, 0.0, 0.0], &other).unwrap().normal(), vec![0.0; 3]);
+    }
+
+    #[test]
+    fn short_or_missing_motion_misses() {
+        let wall = AABB::new(3.0, -5.0, -5.0, 4.0, 5.0, 5.0);
+        assert_eq!(unit_box().sweep(&[1.0, 0.0, 0.0], &wall), None);
+        assert_eq!(unit_box().sweep(&[-4.0, 0.0, 0.0], &wall), None);
+        assert_eq!(unit_box().sweep(&[4.0, 10.0, 0.0], &wall), None);
+        assert_eq!(unit_box().sweep(&[4.0, 0.0], &wall), None);
+        assert_eq!(unit_box().sweep(&[], &wall), None);
+    }
+
+    #[test]
+    fn a_stationary_axis_without_overlap_never_hits() {
+        let above = AABB::new(3.0, 2.0, 0.0, 4.0, 3.0, 1.0);
+        assert_eq!(unit_box().sweep(&[4.0, 0.0, 0.0], &above), None);
+        // Only touching on the stationary axis is not an overlap either.
+        let level = AABB::new(3.0, 1.0, 0.0, 4.0, 2.0, 1.0);
+        assert_eq!(unit_box().sweep(&[4.0, 0.0, 0.0], &level), None);
+    }
+
+    #[test]
+    fn touching_boxes_block_only_motion_into_each_other() {
+        let floor = AABB::new(-10.0, -1.0, -10.0, 10.0, 0.0, 10.0);
+        let hit = unit_box().sweep_hit(&[1.0, -1.0, 0.0], &floor).unwrap();
+        assert_eq!((hit.time(), hit.normal()), (0.0, vec![0.0, 1.0, 0.0]));
+        // Sliding along it or lifting off is free.
+        assert_eq!(unit_box().sweep(&[1.0, 0.0, 0.0], &floor), None);
+        assert_eq!(unit_box().sweep(&[0.0, 1.0, 0.0], &floor), None);
+    }
+}
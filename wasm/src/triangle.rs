@@ -0,0 +1,116 @@
+use wasm_bindgen::prelude::*;
+use crate::math::{self, Point3};
+use crate::{point3, Ray};
+
+#[wasm_bindgen]
+impl Ray {
+    /// Möller–Trumbore ray/triangle test returning the hit distance. Triangles
+    /// wound counter-clockwise as seen by the ray are front faces; with
+    /// `cull_backface` set, back faces are ignored. Edges count as hits.
+    pub fn intersects_triangle(&self, v0: &[f32], v1: &[f32], v2: &[f32], cull_backface: bool) -> Option<f32> {
+        self.triangle_hit(&point3(v0), &point3(v1), &point3(v2), cull_backface).map(|(t, _, _)| t)
+    }
+
+    /// Same test as `intersects_triangle`, returning `[t, u, v]` where the hit
+    /// point is `(1 - u - v) * v0 + u * v1 + v * v2`.
+    pub fn intersect_triangle_barycentric(
+        &self,
+        v0: &[f32],
+        v1: &[f32],
+        v2: &[f32],
+        cull_backface: bool,
+    ) -> Option<Vec<f32>> {
+        self.triangle_hit(&point3(v0), &point3(v1), &point3(v2), cull_backface)
+            .map(|(t, u, v)| vec![t, u, v])
+    }
+}
+
+impl Ray {
+    pub(crate) fn triangle_hit(
+        &self,
+        v0: &Point3,
+        v1: &Point3,
+        v2: &Point3,
+        cull_backface: bool,
+    ) -> Option<(f32, f32, f32)> {
+        let edge1 = *v1 - *v0;
+        let edge2 = *v2 - *v0;
+        let p = math::cross(self.direction, edge2);
+        let det = math::dot(edge1, p);
+
+        // Relative tolerance so zero-area and edge-on triangles are rejected
+        // regardless of their scale.
+        let tolerance = f32::EPSILON * math::length(edge1) * math::length(edge2);
+        if (cull_backface && det <= tolerance) || det.abs() <= tolerance {
+            return None;
+        }
+
+        let inv_det = 1.0 / det;
+        let s = self.origin - *v0;
+        let u = math::dot(s, p) * inv_det;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let q = math::cross(s, edge1);
+        let v = math::dot(self.direction, q) * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let t = math::dot(edge2, q) * inv_det;
+        if t >= 0.0 {
+            Some((t, u, v))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: [f32; 3] = [0.0, 0.0, 0.0];
+    const B: [f32; 3] = [1.0, 0.0, 0.0];
+    const C: [f32; 3] = [0.0, 1.0, 0.0];
+
+    #[test]
+    fn front_and_back_faces() {
+        let front = Ray::new(0.2, 0.2, 1.0, 0.0, 0.0, -1.0);
+        assert_eq!(front.intersects_triangle(&A, &B, &C, true), Some(1.0));
+        assert_eq!(front.intersect_triangle_barycentric(&A, &B, &C, true), Some(vec![1.0, 0.2, 0.2]));
+
+        let back = Ray::new(0.2, 0.2, -1.0, 0.0, 0.0, 1.0);
+        assert_eq!(back.intersects_triangle(&A, &B, &C, true), None);
+        assert_eq!(back.intersects_triangle(&A, &B, &C, false), Some(1.0));
+
+        let behind = Ray::new(0.2, 0.2, 1.0, 0.0, 0.0, 1.0);
+        assert_eq!(behind.intersects_triangle(&A, &B, &C, false), None);
+    }
+
+    #[test]
+    fn edges_hit_and_edge_on_rays_miss() {
+        let on_edge = Ray::new(0.5, 0.0, 1.0, 0.0, 0.0, -1.0);
+        assert_eq!(on_edge.intersects_triangle(&A, &B, &C, false), Some(1.0));
+        let on_hypotenuse = Ray::new(0.5, 0.5, 1.0, 0.0, 0.0, -1.0);
+        assert_eq!(on_hypotenuse.intersects_triangle(&A, &B, &C, false), Some(1.0));
+        let outside = Ray::new(0.6, 0.6, 1.0, 0.0, 0.0, -1.0);
+        assert_eq!(outside.intersects_triangle(&A, &B, &C, false), None);
+
+        // Skimming along the triangle's plane.
+        let grazing = Ray::new(-1.0, 0.2, 0.0, 1.0, 0.0, 0.0);
+        assert_eq!(grazing.intersects_triangle(&A, &B, &C, false), None);
+    }
+
+    #[test]
+    fn degenerate_triangles_miss() {
+        let ray = Ray::new(0.2, 0.0, 1.0, 0.0, 0.0, -1.0);
+        assert_eq!(ray.intersects_triangle(&A, &B, &[2.0, 0.0, 0.0], false), None);
+        assert_eq!(ray.intersects_triangle(&A, &A, &A, false), None);
+
+        // The tolerance is relative, so a tiny but well-shaped triangle hits.
+        let tiny = Ray::new(2e-6, 2e-6, 1.0, 0.0, 0.0, -1.0);
+        assert!(tiny.intersects_triangle(&A, &[1e-5, 0.0, 0.0], &[0.0, 1e-5, 0.0], true).is_some());
+    }
+}
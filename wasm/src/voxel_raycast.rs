@@ -0,0 +1,330 @@
+use wasm_bindgen::prelude::*;
+
+use crate::math::{self, Point3, Vector3};
+use crate::{Chunk, Ray};
+
+/// A unit voxel visited by [`VoxelRaycast`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VoxelStep {
+    pub cell: [i32; 3],
+    /// Normal of the face the ray entered through, or zero for the starting cell.
+    pub normal: [i32; 3],
+    /// Distance along the ray at which the cell was entered.
+    pub t: f32,
+}
+
+/// First solid voxel found by [`Ray::raycast_voxels`].
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct VoxelHit {
+    cell: [i32; 3],
+    normal: [i32; 3],
+    t: f32,
+}
+
+#[wasm_bindgen]
+impl VoxelHit {
+    /// Local x coordinate of the hit voxel within its chunk.
+    #[wasm_bindgen(getter)]
+    pub fn x(&self) -> i32 {
+        self.cell[0]
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn y(&self) -> i32 {
+        self.cell[1]
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn z(&self) -> i32 {
+        self.cell[2]
+    }
+
+    /// Distance along the ray at which the voxel was entered.
+    #[wasm_bindgen(getter)]
+    pub fn t(&self) -> f32 {
+        self.t
+    }
+
+    /// Unit axis normal of the face the ray entered through, or zero when the
+    /// ray started inside the voxel.
+    #[wasm_bindgen(getter)]
+    pub fn normal(&self) -> Vec<f32> {
+        self.normal.iter().map(|&n| n as f32).collect()
+    }
+
+    /// Cell next to the hit face, where a voxel placed against the hit would
+    /// go, or `None` when the ray started inside the voxel. It may lie just
+    /// outside the chunk.
+    pub fn placement_cell(&self) -> Option<Vec<i32>> {
+        if self.normal == [0; 3] {
+            return None;
+        }
+        Some((0..3).map(|axis| self.cell[axis] + self.normal[axis]).collect())
+    }
+}
+
+/// Amanatides-Woo 3D-DDA over a grid of unit voxels, yielding cells in the
+/// order the ray visits them until `max_distance` is exceeded.
+pub struct VoxelRaycast {
+    cell: [i32; 3],
+    step: [i32; 3],
+    t_max: [f32; 3],
+    t_delta: [f32; 3],
+    normal: [i32; 3],
+    t: f32,
+    max_distance: f32,
+    done: bool,
+}
+
+impl VoxelRaycast {
+    pub fn new(origin: Point3, direction: Vector3, max_distance: f32) -> VoxelRaycast {
+        let mut cell = [0; 3];
+        let mut step = [0; 3];
+        let mut t_max = [f32::INFINITY; 3];
+        let mut t_delta = [f32::INFINITY; 3];
+
+        for axis in 0..3 {
+            let o = origin[axis];
+            let d = direction[axis];
+            let floor = o.floor();
+            cell[axis] = floor as i32;
+
+            if d > 0.0 {
+                step[axis] = 1;
+                t_delta[axis] = 1.0 / d;
+                t_max[axis] = (floor + 1.0 - o) / d;
+            } else if d < 0.0 {
+                step[axis] = -1;
+                t_delta[axis] = -1.0 / d;
+                if o == floor {
+                    // Sitting exactly on a boundary while moving backwards:
+                    // the ray is already in the lower cell.
+                    cell[axis] -= 1;
+                    t_max[axis] = t_delta[axis];
+                } else {
+                    t_max[axis] = (floor - o) / d;
+                }
+            }
+        }
+
+        let finite = (0..3).all(|axis| direction[axis].is_finite());
+
+        VoxelRaycast {
+            cell,
+            step,
+            t_max,
+            t_delta,
+            normal: [0; 3],
+            t: 0.0,
+            max_distance,
+            done: !finite || max_distance < 0.0,
+        }
+    }
+}
+
+impl Iterator for VoxelRaycast {
+    type Item = VoxelStep;
+
+    fn next(&mut self) -> Option<VoxelStep> {
+        if self.done {
+            return None;
+        }
+
+        let current = VoxelStep { cell: self.cell, normal: self.normal, t: self.t };
+
+        let mut axis = 0;
+        for candidate in 1..3 {
+            if self.t_max[candidate] < self.t_max[axis] {
+                axis = candidate;
+            }
+        }
+
+        if self.step[axis] == 0 || self.t_max[axis] > self.max_distance {
+            self.done = true;
+        } else {
+            self.t = self.t_max[axis];
+            self.cell[axis] += self.step[axis];
+            self.t_max[axis] += self.t_delta[axis];
+            self.normal = [0; 3];
+            self.normal[axis] = -self.step[axis];
+        }
+
+        Some(current)
+    }
+}
+
+#[wasm_bindgen]
+impl Ray {
+    /// Visits the unit voxels along the ray up to `max_distance`, which must be
+    /// finite. Returns six integers per cell: `x, y, z` followed by the normal of
+    /// the face the ray entered through (all zero for the starting cell).
+    pub fn traverse_voxels(&self, max_distance: f32) -> Vec<i32> {
+        if !max_distance.is_finite() {
+            return Vec::new();
+        }
+
+        VoxelRaycast::new(self.origin, self.direction, max_distance)
+            .flat_map(|step| step.cell.into_iter().chain(step.normal))
+            .collect()
+    }
+
+    /// First non-air voxel of `chunk` along the ray within `max_distance`.
+    /// The ray is clipped to the chunk's bounds before walking its cells, so
+    /// rays starting outside the chunk work too.
+    pub fn raycast_voxels(&self, chunk: &Chunk, max_distance: f32) -> Option<VoxelHit> {
+        if !max_distance.is_finite() {
+            return None;
+        }
+
+        let bounds = chunk.bounds();
+        let (t_enter, t_exit, axis) = self.slab_entry(&bounds)?;
+        let start = t_enter.max(0.0);
+        if start > max_distance {
+            return None;
+        }
+
+        // Normal of the chunk face the ray came in through, for a hit on the
+        // very first cell walked.
+        let mut entry_normal = [0; 3];
+        if t_enter > 0.0 {
+            entry_normal[axis] = if self.direction[axis] > 0.0 { -1 } else { 1 };
+        }
+
+        // Walk in voxel units, scaling the direction too so `t` stays in the
+        // ray's own units.
+        let scale = chunk.voxel_size();
+        let local = (self.origin + self.direction * start - math::to_vector(bounds.min)) * (1.0 / scale);
+        let limit = max_distance.min(t_exit) - start;
+
+        VoxelRaycast::new(local, self.direction * (1.0 / scale), limit)
+            .find(|step| chunk.get(step.cell[0], step.cell[1], step.cell[2]) != 0)
+            .map(|step| VoxelHit {
+                cell: step.cell,
+                normal: if step.normal == [0; 3] { entry_normal } else { step.normal },
+                t: start + step.t,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn traverse_voxels_steps_one_face_at_a_time() {
+        let ray = Ray::new(0.5, 0.5, 0.5, 1.0, 0.0, 0.0);
+        assert_eq!(
+            ray.traverse_voxels(3.0),
+            vec![0, 0, 0, 0, 0, 0, 1, 0, 0, -1, 0, 0, 2, 0, 0, -1, 0, 0, 3, 0, 0, -1, 0, 0]
+        );
+
+        let cells: Vec<VoxelStep> =
+            VoxelRaycast::new(math::point(0.2, 0.7, -3.3), math::vector(0.3, -0.5, 0.8).normalize(), 20.0).collect();
+        assert!(cells.len() > 20);
+        for pair in cells.windows(2) {
+            let moved: i32 = (0..3).map(|axis| (pair[1].cell[axis] - pair[0].cell[axis]).abs()).sum();
+            assert_eq!(moved, 1);
+            assert!(pair[1].t >= pair[0].t);
+        }
+    }
+
+    #[test]
+    fn traverse_voxels_floors_negative_coordinates() {
+        // -0.5 is in cell -1, not 0.
+        let ray = Ray::new(-0.5, 0.5, 0.5, -1.0, 0.0, 0.0);
+        assert_eq!(ray.traverse_voxels(1.0), vec![-1, 0, 0, 0, 0, 0, -2, 0, 0, 1, 0, 0]);
+
+        let ray = Ray::new(-0.25, -1.5, -2.75, 0.0, 0.0, 1.0);
+        assert_eq!(ray.traverse_voxels(1.0), vec![-1, -2, -3, 0, 0, 0, -1, -2, -2, 0, 0, -1]);
+
+        // Starting on a boundary while moving down stays in the upper cell.
+        let ray = Ray::new(1.0, 0.5, 0.5, -1.0, 0.0, 0.0);
+        assert_eq!(ray.traverse_voxels(0.5), vec![0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn raycast_voxels_finds_the_first_solid_cell() {
+        let mut chunk = Chunk::new(10.0, 0.0, 0.0);
+        chunk.set(0, 5, 5, 1);
+        let ray = Ray::new(5.0, 5.5, 5.5, 1.0, 0.0, 0.0);
+        let hit = ray.raycast_voxels(&chunk, 100.0).unwrap();
+        assert_eq!((hit.x(), hit.y(), hit.z(), hit.t()), (0, 5, 5, 5.0));
+        assert!(ray.raycast_voxels(&chunk, 4.9).is_none());
+
+        // Entering the chunk straight into a solid top layer.
+        chunk.set(0, 5, 5, 0);
+        chunk.set(17, 31, 23, 1);
+        let down = Ray::new(27.5, 50.0, 23.5, 0.0, -1.0, 0.0);
+        let hit = down.raycast_voxels(&chunk, 1000.0).unwrap();
+        assert_eq!((hit.x(), hit.y(), hit.t()), (17, 31, 18.0));
+
+        // Starting inside a solid voxel hits it at once.
+        let inside = Ray::new(27.5, 31.5, 23.5, 0.0, 1.0, 0.0);
+        assert_eq!(inside.raycast_voxels(&chunk, 1.0).unwrap().t(), 0.0);
+    }
+
+    #[test]
+    fn raycast_voxels_reaches_deep_cells() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        chunk.set(17, 9, 23, 4);
+        let diagonal = Ray::new(0.5, 0.5, 0.5, 16.7, 8.6, 22.8);
+        let hit = diagonal.raycast_voxels(&chunk, 100.0).unwrap();
+        assert_eq!((hit.x(), hit.y(), hit.z()), (17, 9, 23));
+
+        let down = Ray::new(17.5, 50.0, 23.5, 0.0, -1.0, 0.0);
+        let hit = down.raycast_voxels(&chunk, 1000.0).unwrap();
+        assert_eq!((hit.y(), hit.t()), (9, 40.0));
+
+        chunk.set(20, 5, 5, 2);
+        let far = Ray::new(5.0, 5.5, 5.5, 1.0, 0.0, 0.0).raycast_voxels(&chunk, 100.0).unwrap();
+        assert_eq!((far.x(), far.t()), (20, 15.0));
+    }
+
+    #[test]
+    fn raycast_voxels_misses_cleanly() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        chunk.set(17, 9, 23, 4);
+        assert!(Ray::new(1.5, 1.5, 1.5, 0.0, 1.0, 0.0).raycast_voxels(&chunk, 1000.0).is_none());
+        assert!(Ray::new(17.5, 50.0, 23.5, 0.0, 1.0, 0.0).raycast_voxels(&chunk, 1000.0).is_none());
+        assert!(Ray::new(-5.0, 9.5, 23.5, -1.0, 0.0, 0.0).raycast_voxels(&chunk, 1000.0).is_none());
+        assert!(Ray::new(17.5, 9.5, -5.0, 0.0, 0.0, 1.0).raycast_voxels(&Chunk::new(0.0, 0.0, 0.0), 1000.0).is_none());
+    }
+
+    #[test]
+    fn hit_normal_faces_back_along_each_axis() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        chunk.set(10, 10, 10, 1);
+        for axis in 0..3 {
+            for sign in [1.0, -1.0] {
+                let mut direction = [0.0; 3];
+                direction[axis] = sign;
+                let origin = direction.map(|d| 10.5 - d * 8.0);
+                let ray = Ray::new(origin[0], origin[1], origin[2], direction[0], direction[1], direction[2]);
+                let hit = ray.raycast_voxels(&chunk, 100.0).unwrap();
+                assert_eq!((hit.x(), hit.y(), hit.z(), hit.t()), (10, 10, 10, 7.5));
+
+                let mut normal = vec![0.0; 3];
+                normal[axis] = -sign;
+                assert_eq!(hit.normal(), normal);
+                let placement: Vec<i32> = (0..3).map(|a| 10 - direction[a] as i32).collect();
+                assert_eq!(hit.placement_cell(), Some(placement));
+            }
+        }
+    }
+
+    #[test]
+    fn hit_from_inside_has_no_normal() {
+        let mut chunk = Chunk::new(0.0, 0.0, 0.0);
+        chunk.set(10, 10, 10, 1);
+        let hit = Ray::new(10.5, 10.5, 10.5, 1.0, 0.0, 0.0).raycast_voxels(&chunk, 100.0).unwrap();
+        assert_eq!(hit.normal(), vec![0.0, 0.0, 0.0]);
+        assert_eq!(hit.placement_cell(), None);
+
+        // A hit on the chunk border places outside the chunk.
+        chunk.set(0, 3, 3, 1);
+        let hit = Ray::new(-5.0, 3.5, 3.5, 1.0, 0.0, 0.0).raycast_voxels(&chunk, 100.0).unwrap();
+        assert_eq!(hit.normal(), vec![-1.0, 0.0, 0.0]);
+        assert_eq!(hit.placement_cell(), Some(vec![-1, 3, 3]));
+    }
+}
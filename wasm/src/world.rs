@@ -0,0 +1,969 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::math::{self, Point3, Vector3};
+use crate::{Ray, SpatialHashGrid, AABB};
+
+// Cell size of the world's spatial index, in world units.
+const INDEX_CELL_SIZE: f32 = 16.0;
+
+/// Generational entity handle. A despawned entity's slot may be reused, but
+/// with a new generation, so stale handles stay dead.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+#[wasm_bindgen]
+impl Entity {
+    #[wasm_bindgen(getter)]
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+/// World-space position component.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Position(pub Point3);
+
+/// World-space velocity component, in units per second.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Velocity(pub Vector3);
+
+// Type-erased column of one component type within an archetype.
+trait Column {
+    fn empty(&self) -> Box<dyn Column>;
+    fn swap_remove(&mut self, row: usize);
+    // Swap-removes `row` and pushes it onto `dest`, which must hold the same type.
+    fn move_row(&mut self, row: usize, dest: &mut dyn Column);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: 'static> Column for Vec<T> {
+    fn empty(&self) -> Box<dyn Column> {
+        Box::new(Vec::<T>::new())
+    }
+
+    fn swap_remove(&mut self, row: usize) {
+        Vec::swap_remove(self, row);
+    }
+
+    fn move_row(&mut self, row: usize, dest: &mut dyn Column) {
+        let value = Vec::swap_remove(self, row);
+        dest.as_any_mut().downcast_mut::<Vec<T>>().unwrap().push(value);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+// All entities with exactly the component types in `types`, stored as one
+// column per type (sorted by `TypeId`) with `entities[row]` owning each row.
+// `ticks` parallels `columns`, holding the tick each component last changed.
+struct Archetype {
+    types: Vec<TypeId>,
+    columns: Vec<Box<dyn Column>>,
+    ticks: Vec<Vec<u32>>,
+    entities: Vec<u32>,
+}
+
+impl Archetype {
+    fn new(types: Vec<TypeId>, columns: Vec<Box<dyn Column>>) -> Archetype {
+        let ticks = vec![Vec::new(); types.len()];
+        Archetype { types, columns, ticks, entities: Vec::new() }
+    }
+
+    fn column_index<T: 'static>(&self) -> Option<usize> {
+        self.types.binary_search(&TypeId::of::<T>()).ok()
+    }
+
+    fn column<T: 'static>(&self) -> Option<&Vec<T>> {
+        self.columns[self.column_index::<T>()?].as_any().downcast_ref()
+    }
+
+    fn column_mut<T: 'static>(&mut self) -> Option<&mut Vec<T>> {
+        let i = self.column_index::<T>()?;
+        self.columns[i].as_any_mut().downcast_mut()
+    }
+
+    // Entities alongside a mutable `A` column with its change ticks and a
+    // shared `B` column.
+    fn column_pair_mut<A: 'static, B: 'static>(&mut self) -> Option<PairColumns<'_, A, B>> {
+        let a = self.column_index::<A>()?;
+        let b = self.column_index::<B>()?;
+        assert_ne!(a, b, "component types of a pair query must differ");
+
+        let ticks = &mut self.ticks[a];
+        let (a, b) = two_mut(&mut self.columns, a, b);
+        Some((&self.entities, a.as_any_mut().downcast_mut()?, ticks, b.as_any().downcast_ref()?))
+    }
+
+    // Swap-removes `row`, returning the entity that moved into it, if any.
+    fn swap_remove_entity(&mut self, row: usize) -> Option<u32> {
+        self.entities.swap_remove(row);
+        self.entities.get(row).copied()
+    }
+}
+
+type PairColumns<'a, A, B> = (&'a [u32], &'a mut Vec<A>, &'a mut Vec<u32>, &'a Vec<B>);
+
+// Where an entity slot's components live.
+#[derive(Clone, Copy)]
+struct Slot {
+    generation: u32,
+    archetype: usize,
+    row: usize,
+}
+
+// Collide-and-slide passes `move_and_slide` makes before giving up on the
+// rest of the motion.
+const MAX_SLIDES: usize = 4;
+
+// Archetype 0 holds entities without components.
+const EMPTY_ARCHETYPE: usize = 0;
+
+/// Entities grouped into archetypes by their set of component types, with
+/// each component type stored contiguously, so iterating a few component
+/// types is a linear scan. Adding or removing a component moves the entity to
+/// another archetype. Generic access is Rust-only; JS uses the concrete
+/// `insert_*` methods.
+///
+/// `AABB` components are mirrored into a spatial index for region and ray
+/// queries. The index follows `insert`, `remove` and `despawn`; edits made
+/// through `get_mut` are not picked up until the box is inserted again.
+///
+/// Every component records the tick it was last inserted or borrowed
+/// mutably at, for `changed_since`. Ticks start at 1 and advance with `tick`.
+#[wasm_bindgen]
+pub struct World {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+    archetypes: Vec<Archetype>,
+    archetype_ids: HashMap<Vec<TypeId>, usize>,
+    index: SpatialHashGrid,
+    change_tick: u32,
+}
+
+impl Default for World {
+    fn default() -> World {
+        World {
+            slots: Vec::new(),
+            free: Vec::new(),
+            archetypes: vec![Archetype::new(Vec::new(), Vec::new())],
+            archetype_ids: HashMap::from([(Vec::new(), EMPTY_ARCHETYPE)]),
+            index: SpatialHashGrid::new(INDEX_CELL_SIZE),
+            change_tick: 1,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl World {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> World {
+        World::default()
+    }
+
+    pub fn spawn(&mut self) -> Entity {
+        let empty = &mut self.archetypes[EMPTY_ARCHETYPE];
+        let location = Slot { generation: 0, archetype: EMPTY_ARCHETYPE, row: empty.entities.len() };
+
+        let index = match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                *slot = Slot { generation: slot.generation, ..location };
+                index
+            }
+            None => {
+                self.slots.push(location);
+                self.slots.len() as u32 - 1
+            }
+        };
+
+        empty.entities.push(index);
+        self.entity_at(index)
+    }
+
+    /// Removes the entity and all of its components, returning `false` if the
+    /// handle was already dead.
+    pub fn despawn(&mut self, entity: &Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+
+        let slot = self.slots[entity.index as usize];
+        let archetype = &mut self.archetypes[slot.archetype];
+        for column in &mut archetype.columns {
+            column.swap_remove(slot.row);
+        }
+        for ticks in &mut archetype.ticks {
+            ticks.swap_remove(slot.row);
+        }
+        if let Some(moved) = archetype.swap_remove_entity(slot.row) {
+            self.slots[moved as usize].row = slot.row;
+        }
+
+        self.index.remove(entity.index);
+        self.slots[entity.index as usize].generation += 1;
+        self.free.push(entity.index);
+        true
+    }
+
+    pub fn is_alive(&self, entity: &Entity) -> bool {
+        // Despawning bumps the slot's generation, so a free slot never matches
+        // a handle that was handed out.
+        self.slots.get(entity.index as usize).map(|slot| slot.generation) == Some(entity.generation)
+    }
+
+    pub fn insert_position(&mut self, entity: &Entity, x: f32, y: f32, z: f32) -> bool {
+        self.insert(*entity, Position(math::point(x, y, z)))
+    }
+
+    pub fn position(&self, entity: &Entity) -> Option<Vec<f32>> {
+        self.get::<Position>(*entity).map(|Position(p)| vec![p.x, p.y, p.z])
+    }
+
+    pub fn insert_velocity(&mut self, entity: &Entity, vx: f32, vy: f32, vz: f32) -> bool {
+        self.insert(*entity, Velocity(math::vector(vx, vy, vz)))
+    }
+
+    pub fn velocity(&self, entity: &Entity) -> Option<Vec<f32>> {
+        self.get::<Velocity>(*entity).map(|Velocity(v)| vec![v.x, v.y, v.z])
+    }
+
+    pub fn insert_aabb(&mut self, entity: &Entity, aabb: &AABB) -> bool {
+        self.insert(*entity, *aabb)
+    }
+
+    pub fn aabb(&self, entity: &Entity) -> Option<AABB> {
+        self.get::<AABB>(*entity).copied()
+    }
+
+    /// Alive entities whose AABB intersects `region`, in index order.
+    pub fn query_region(&self, region: &AABB) -> Vec<Entity> {
+        self.index.query(region).into_iter().map(|index| self.entity_at(index)).collect()
+    }
+
+    /// Nearest entity whose AABB is hit by `ray`: the first of `raycast_all`
+    /// with no distance limit.
+    pub fn raycast(&self, ray: &Ray) -> Option<Entity> {
+        self.raycast_all(ray, f32::INFINITY).first().copied()
+    }
+
+    /// Moves every entity with a `Position` and a `Velocity` by `velocity *
+    /// dt`. Those that also have an `AABB` have it moved by the same amount
+    /// and their spatial index entry updated.
+    pub fn integrate(&mut self, dt: f32) {
+        let moved: Vec<(Entity, Vector3)> = self
+            .iter_pair_mut::<Position, Velocity>()
+            .map(|(entity, Position(position), Velocity(velocity))| {
+                let delta = *velocity * dt;
+                *position += delta;
+                (entity, delta)
+            })
+            .collect();
+
+        for (entity, delta) in moved {
+            if let Some(aabb) = self.get_mut::<AABB>(entity) {
+                aabb.min += delta;
+                aabb.max += delta;
+                let aabb = *aabb;
+                self.index.update(entity.index, &aabb);
+            }
+        }
+    }
+
+    /// Moves the entity's AABB by up to `displacement` (`[x, y, z]`), sliding
+    /// along the boxes of other entities it runs into instead of stopping at
+    /// them, and returns the displacement actually applied. A box that starts
+    /// inside others is first pushed out of each along its axis of least
+    /// overlap. Any `Position` moves by the same amount. Entities without an
+    /// `AABB` don't move.
+    pub fn move_and_slide(&mut self, entity: &Entity, displacement: &[f32]) -> Vec<f32> {
+        let Some(&start) = self.get::<AABB>(*entity) else {
+            return vec![0.0; 3];
+        };
+        let mut aabb = start;
+        for other in self.obstacles(entity, &aabb) {
+            if let Some(push) = aabb.penetration(&other) {
+                let push = math::vector(push[0], push[1], push[2]);
+                aabb = AABB { min: aabb.min + push, max: aabb.max + push };
+            }
+        }
+
+        let mut remaining = math::vector(displacement[0], displacement[1], displacement[2]);
+        for _ in 0..MAX_SLIDES {
+            if remaining == math::zero() {
+                break;
+            }
+
+            let moved = AABB { min: aabb.min + remaining, max: aabb.max + remaining };
+            let hit = self
+                .obstacles(entity, &aabb.union(&moved))
+                .iter()
+                .filter_map(|other| aabb.sweep_against(&remaining, other))
+                .min_by(|a, b| a.time.total_cmp(&b.time));
+
+            let Some(hit) = hit else {
+                aabb = moved;
+                break;
+            };
+
+            let step = remaining * hit.time;
+            aabb = AABB { min: aabb.min + step, max: aabb.max + step };
+            if hit.normal == math::zero() {
+                break;
+            }
+
+            // Drop the part of the leftover motion that pushes into the
+            // contact face and keep the part along it.
+            remaining *= 1.0 - hit.time;
+            remaining -= hit.normal * math::dot(remaining, hit.normal);
+        }
+
+        let offset = aabb.min - start.min;
+        self.insert(*entity, aabb);
+        if let Some(Position(position)) = self.get_mut::<Position>(*entity) {
+            *position += offset;
+        }
+        vec![offset.x, offset.y, offset.z]
+    }
+
+    /// Tick that changes are currently recorded at.
+    #[wasm_bindgen(getter)]
+    pub fn current_tick(&self) -> u32 {
+        self.change_tick
+    }
+
+    /// Advances to the next tick, returning the one that just ended. Passing
+    /// it to `changed_since` later yields what changed from then on.
+    pub fn tick(&mut self) -> u32 {
+        self.change_tick += 1;
+        self.change_tick - 1
+    }
+
+    /// Alive entities with a component inserted or mutably borrowed at a
+    /// tick later than `tick`, in index order.
+    pub fn changed_since(&self, tick: u32) -> Vec<Entity> {
+        let mut indices: Vec<u32> = self
+            .archetypes
+            .iter()
+            .flat_map(|archetype| {
+                archetype
+                    .entities
+                    .iter()
+                    .enumerate()
+                    .filter(|&(row, _)| archetype.ticks.iter().any(|ticks| ticks[row] > tick))
+                    .map(|(_, &index)| index)
+            })
+            .collect();
+
+        indices.sort_unstable();
+        indices.into_iter().map(|index| self.entity_at(index)).collect()
+    }
+
+    /// Alive entities whose AABB the ray enters within `max_distance`,
+    /// nearest entry first. Boxes containing the origin count as entered at
+    /// distance 0.
+    pub fn raycast_all(&self, ray: &Ray, max_distance: f32) -> Vec<Entity> {
+        if max_distance.is_nan() || max_distance < 0.0 {
+            return Vec::new();
+        }
+
+        let region = if max_distance.is_finite() {
+            let end = ray.origin + ray.direction * max_distance;
+            AABB { min: math::min(ray.origin, end), max: math::max(ray.origin, end) }
+        } else {
+            let (lo, hi) = (f32::NEG_INFINITY, f32::INFINITY);
+            AABB::new(lo, lo, lo, hi, hi, hi)
+        };
+
+        let mut hits: Vec<(f32, u32)> = self
+            .index
+            .query(&region)
+            .into_iter()
+            .filter_map(|index| {
+                let aabb = self.get::<AABB>(self.entity_at(index))?;
+                let (t, _) = ray.aabb_interval(aabb)?;
+                (t <= max_distance).then_some((t, index))
+            })
+            .collect();
+
+        hits.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        hits.into_iter().map(|(_, index)| self.entity_at(index)).collect()
+    }
+}
+
+impl World {
+    /// Adds or replaces a component, returning `false` if the entity is dead.
+    pub fn insert<T: 'static>(&mut self, entity: Entity, component: T) -> bool {
+        if !self.is_alive(&entity) {
+            return false;
+        }
+
+        if let Some(aabb) = (&component as &dyn Any).downcast_ref::<AABB>() {
+            self.index.insert(entity.index, aabb);
+        }
+
+        let slot = self.slots[entity.index as usize];
+        let archetype = &mut self.archetypes[slot.archetype];
+        if let Some(i) = archetype.column_index::<T>() {
+            archetype.column_mut::<T>().unwrap()[slot.row] = component;
+            archetype.ticks[i][slot.row] = self.change_tick;
+            return true;
+        }
+
+        let mut types = self.archetypes[slot.archetype].types.clone();
+        let position = types.binary_search(&TypeId::of::<T>()).unwrap_err();
+        types.insert(position, TypeId::of::<T>());
+        let target = self.archetype_with(slot.archetype, types, |source| {
+            let mut columns: Vec<_> = source.columns.iter().map(|column| column.empty()).collect();
+            columns.insert(position, Box::new(Vec::<T>::new()));
+            columns
+        });
+
+        self.move_entity(entity.index, target);
+        self.archetypes[target].column_mut::<T>().unwrap().push(component);
+        self.archetypes[target].ticks[position].push(self.change_tick);
+        true
+    }
+
+    pub fn get<T: 'static>(&self, entity: Entity) -> Option<&T> {
+        if !self.is_alive(&entity) {
+            return None;
+        }
+
+        let slot = self.slots[entity.index as usize];
+        self.archetypes[slot.archetype].column::<T>()?.get(slot.row)
+    }
+
+    pub fn get_mut<T: 'static>(&mut self, entity: Entity) -> Option<&mut T> {
+        if !self.is_alive(&entity) {
+            return None;
+        }
+
+        let slot = self.slots[entity.index as usize];
+        let archetype = &mut self.archetypes[slot.archetype];
+        let i = archetype.column_index::<T>()?;
+        archetype.ticks[i][slot.row] = self.change_tick;
+        archetype.column_mut::<T>()?.get_mut(slot.row)
+    }
+
+    pub fn remove<T: 'static>(&mut self, entity: Entity) -> Option<T> {
+        if !self.is_alive(&entity) {
+            return None;
+        }
+
+        let slot = self.slots[entity.index as usize];
+        let mut types = self.archetypes[slot.archetype].types.clone();
+        let position = types.binary_search(&TypeId::of::<T>()).ok()?;
+        types.remove(position);
+
+        if TypeId::of::<T>() == TypeId::of::<AABB>() {
+            self.index.remove(entity.index);
+        }
+
+        let component = self.archetypes[slot.archetype].column_mut::<T>().unwrap().swap_remove(slot.row);
+        self.archetypes[slot.archetype].ticks[position].swap_remove(slot.row);
+        let target = self.archetype_with(slot.archetype, types, |source| {
+            let mut columns: Vec<_> = source.columns.iter().map(|column| column.empty()).collect();
+            columns.remove(position);
+            columns
+        });
+
+        self.move_entity(entity.index, target);
+        Some(component)
+    }
+
+    /// Every alive entity with a `T`, grouped by archetype.
+    pub fn iter<T: 'static>(&self) -> impl Iterator<Item = (Entity, &T)> + '_ {
+        self.archetypes
+            .iter()
+            .filter_map(|archetype| Some((archetype, archetype.column::<T>()?)))
+            .flat_map(move |(archetype, column)| {
+                archetype
+                    .entities
+                    .iter()
+                    .zip(column)
+                    .map(move |(&index, component)| (self.entity_at(index), component))
+            })
+    }
+
+    /// Every alive entity with both an `A` and a `B`, grouped by archetype.
+    pub fn iter_pair<A: 'static, B: 'static>(&self) -> impl Iterator<Item = (Entity, &A, &B)> + '_ {
+        self.archetypes
+            .iter()
+            .filter_map(|archetype| Some((archetype, archetype.column::<A>()?, archetype.column::<B>()?)))
+            .flat_map(move |(archetype, a, b)| {
+                archetype
+                    .entities
+                    .iter()
+                    .zip(a.iter().zip(b))
+                    .map(move |(&index, (a, b))| (self.entity_at(index), a, b))
+            })
+    }
+
+    /// Like `iter_pair`, with `A` borrowed mutably. `A` and `B` must differ.
+    /// Every `A` yielded counts as changed at the current tick.
+    pub fn iter_pair_mut<A: 'static, B: 'static>(&mut self) -> impl Iterator<Item = (Entity, &mut A, &B)> + '_ {
+        let slots = &self.slots;
+        let change_tick = self.change_tick;
+        self.archetypes
+            .iter_mut()
+            .filter_map(|archetype| archetype.column_pair_mut::<A, B>())
+            .flat_map(move |(entities, a, ticks, b)| {
+                entities.iter().zip(a.iter_mut().zip(ticks.iter_mut()).zip(b)).map(move |(&index, ((a, tick), b))| {
+                    *tick = change_tick;
+                    (Entity { index, generation: slots[index as usize].generation }, a, b)
+                })
+            })
+    }
+
+    // Boxes of entities other than `entity` that intersect `region`.
+    fn obstacles(&self, entity: &Entity, region: &AABB) -> Vec<AABB> {
+        self.query_region(region)
+            .into_iter()
+            .filter(|other| other != entity)
+            .filter_map(|other| self.get::<AABB>(other).copied())
+            .collect()
+    }
+
+    // Handle for a slot known to be alive, such as one found in an archetype.
+    fn entity_at(&self, index: u32) -> Entity {
+        Entity { index, generation: self.slots[index as usize].generation }
+    }
+
+    // Index of the archetype for `types`, creating it with `columns` built
+    // from the `source` archetype if it doesn't exist yet.
+    fn archetype_with(
+        &mut self,
+        source: usize,
+        types: Vec<TypeId>,
+        columns: impl FnOnce(&Archetype) -> Vec<Box<dyn Column>>,
+    ) -> usize {
+        if let Some(&id) = self.archetype_ids.get(&types) {
+            return id;
+        }
+
+        let columns = columns(&self.archetypes[source]);
+        self.archetypes.push(Archetype::new(types.clone(), columns));
+        self.archetype_ids.insert(types, self.archetypes.len() - 1);
+        self.archetypes.len() - 1
+    }
+
+    // Moves the entity's row into `target`, carrying over every component
+    // type both archetypes share. The caller takes care of the rest: the row
+    // of a column only the source has must already be removed, and a column
+    // only `target` has must be pushed to afterwards.
+    fn move_entity(&mut self, index: u32, target: usize) {
+        let slot = self.slots[index as usize];
+        let (source, dest) = two_mut(&mut self.archetypes, slot.archetype, target);
+        for (i, ty) in source.types.iter().enumerate() {
+            if let Ok(j) = dest.types.binary_search(ty) {
+                source.columns[i].move_row(slot.row, dest.columns[j].as_mut());
+                dest.ticks[j].push(source.ticks[i].swap_remove(slot.row));
+            }
+        }
+
+        if let Some(moved) = source.swap_remove_entity(slot.row) {
+            self.slots[moved as usize].row = slot.row;
+        }
+
+        dest.entities.push(index);
+        let row = dest.entities.len() - 1;
+        self.slots[index as usize] = Slot { generation: slot.generation, archetype: target, row };
+    }
+}
+
+fn two_mut<T>(items: &mut [T], a: usize, b: usize) -> (&mut T, &mut T) {
+    assert_ne!(a, b);
+    if a < b {
+        let (low, high) = items.split_at_mut(b);
+        (&mut low[a], &mut high[0])
+    } else {
+        let (low, high) = items.split_at_mut(a);
+        (&mut high[0], &mut low[b])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::lcg;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Speed(f32);
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Name(String);
+
+    #[test]
+    fn archetypes_match_a_map_reference() {
+        let mut world = World::new();
+        let mut positions: HashMap<u32, Position> = HashMap::new();
+        let mut speeds: HashMap<u32, Speed> = HashMap::new();
+        let mut names: HashMap<u32, Name> = HashMap::new();
+        let mut next = lcg(7);
+        let mut rnd = move |n: u64| next() as u64 % n;
+
+        let mut alive: Vec<Entity> = (0..100_000).map(|_| world.spawn()).collect();
+        for &entity in &alive {
+            if rnd(2) == 0 {
+                let position = Position(math::point(entity.index() as f32, 0.0, 0.0));
+                world.insert(entity, position);
+                positions.insert(entity.index(), position);
+            }
+            if rnd(3) != 0 {
+                let speed = Speed(rnd(100) as f32);
+                world.insert(entity, speed);
+                speeds.insert(entity.index(), speed);
+            }
+            if rnd(5) == 0 {
+                let name = Name(format!("e{}", entity.index()));
+                world.insert(entity, name.clone());
+                names.insert(entity.index(), name);
+            }
+        }
+
+        for _ in 0..50_000 {
+            let i = rnd(alive.len() as u64) as usize;
+            let entity = alive[i];
+            match rnd(6) {
+                0 => assert_eq!(world.remove::<Speed>(entity), speeds.remove(&entity.index())),
+                1 => assert_eq!(world.remove::<Position>(entity), positions.remove(&entity.index())),
+                2 => {
+                    assert!(world.despawn(&entity));
+                    positions.remove(&entity.index());
+                    speeds.remove(&entity.index());
+                    names.remove(&entity.index());
+                    alive.swap_remove(i);
+                    alive.push(world.spawn());
+                    assert!(!world.is_alive(&entity));
+                }
+                3 => {
+                    let speed = Speed(rnd(10) as f32);
+                    world.insert(entity, speed);
+                    speeds.insert(entity.index(), speed);
+                }
+                4 => match world.get_mut::<Speed>(entity) {
+                    Some(speed) => {
+                        speed.0 += 1.0;
+                        speeds.get_mut(&entity.index()).unwrap().0 += 1.0;
+                    }
+                    None => assert!(!speeds.contains_key(&entity.index())),
+                },
+                _ => assert_eq!(world.remove::<Name>(entity), names.remove(&entity.index())),
+            }
+        }
+
+        for &entity in &alive {
+            assert_eq!(world.get::<Position>(entity), positions.get(&entity.index()));
+            assert_eq!(world.get::<Speed>(entity), speeds.get(&entity.index()));
+            assert_eq!(world.get::<Name>(entity), names.get(&entity.index()));
+        }
+
+        let mut seen = 0;
+        for (entity, position, speed) in world.iter_pair::<Position, Speed>() {
+            assert!(world.is_alive(&entity));
+            assert_eq!(positions.get(&entity.index()), Some(position));
+            assert_eq!(speeds.get(&entity.index()), Some(speed));
+            seen += 1;
+        }
+        assert_eq!(seen, positions.keys().filter(|index| speeds.contains_key(index)).count());
+
+        for (_, speed, _) in world.iter_pair_mut::<Speed, Position>() {
+            speed.0 *= 2.0;
+        }
+        for (index, speed) in speeds.iter_mut() {
+            if positions.contains_key(index) {
+                speed.0 *= 2.0;
+            }
+        }
+        assert_eq!(world.iter::<Speed>().count(), speeds.len());
+        for (entity, speed) in world.iter::<Speed>() {
+            assert_eq!(speeds.get(&entity.index()), Some(speed));
+        }
+    }
+
+    #[test]
+    fn insert_remove_insert_keeps_the_latest_value() {
+        let mut world = World::new();
+        let entity = world.spawn();
+        let other = world.spawn();
+        world.insert(other, Speed(9.0));
+
+        assert!(world.insert(entity, Speed(1.0)));
+        assert_eq!(world.remove::<Speed>(entity), Some(Speed(1.0)));
+        assert_eq!(world.get::<Speed>(entity), None);
+        assert!(world.insert(entity, Speed(2.0)));
+
+        assert_eq!(world.get::<Speed>(entity), Some(&Speed(2.0)));
+        assert_eq!(world.get::<Speed>(other), Some(&Speed(9.0)));
+        assert_eq!(world.iter::<Speed>().count(), 2);
+    }
+
+    #[test]
+    fn despawn_fixes_up_the_row_it_moves() {
+        let mut world = World::new();
+        let entities: Vec<Entity> = (0..3).map(|_| world.spawn()).collect();
+        for (i, &entity) in entities.iter().enumerate() {
+            world.insert(entity, Speed(i as f32));
+        }
+
+        // Removing the first row swaps the last entity into it.
+        assert!(world.despawn(&entities[0]));
+        assert_eq!(world.get::<Speed>(entities[2]), Some(&Speed(2.0)));
+        assert_eq!(world.get::<Speed>(entities[1]), Some(&Speed(1.0)));
+
+        world.get_mut::<Speed>(entities[2]).unwrap().0 = 5.0;
+        assert_eq!(world.get::<Speed>(entities[1]), Some(&Speed(1.0)));
+        assert_eq!(world.remove::<Speed>(entities[2]), Some(Speed(5.0)));
+        assert_eq!(world.get::<Speed>(entities[1]), Some(&Speed(1.0)));
+    }
+
+    #[test]
+    fn unbounded_boxes_are_indexed() {
+        let mut world = World::new();
+        let inf = f32::INFINITY;
+        let sky = world.spawn();
+        world.insert_aabb(&sky, &AABB::new(-inf, 100.0, -inf, inf, 101.0, inf));
+        let crate_box = world.spawn();
+        world.insert_aabb(&crate_box, &AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0));
+
+        assert_eq!(world.query_region(&AABB::new(1e6, 99.0, -1e6, 1e6 + 1.0, 100.5, -1e6 + 1.0)), vec![sky]);
+        assert_eq!(world.query_region(&AABB::new(0.0, 0.0, 0.0, 2.0, 2.0, 2.0)), vec![crate_box]);
+        assert!(world.despawn(&sky));
+        assert!(world.query_region(&AABB::new(0.0, 99.0, 0.0, 1.0, 100.5, 1.0)).is_empty());
+    }
+
+    fn spawn_box(world: &mut World, aabb: AABB) -> Entity {
+        let entity = world.spawn();
+        world.insert_aabb(&entity, &aabb);
+        entity
+    }
+
+    fn assert_close(actual: &[f32], expected: [f32; 3]) {
+        let close = actual.iter().zip(expected).all(|(a, e)| (a - e).abs() < 1e-5);
+        assert!(close, "{actual:?} != {expected:?}");
+    }
+
+    #[test]
+    fn move_and_slide_slides_along_a_wall() {
+        let mut world = World::new();
+        spawn_box(&mut world, AABB::new(2.0, -10.0, -10.0, 3.0, 10.0, 10.0));
+        let mover = spawn_box(&mut world, AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0));
+        world.insert_position(&mover, 0.5, 0.5, 0.5);
+
+        assert_close(&world.move_and_slide(&mover, &[2.0, 0.0, 3.0]), [1.0, 0.0, 3.0]);
+        let aabb = world.aabb(&mover).unwrap();
+        assert!((aabb.max_x() - 2.0).abs() < 1e-5 && (aabb.min_z() - 3.0).abs() < 1e-5);
+        assert_close(&world.position(&mover).unwrap(), [1.5, 0.5, 3.5]);
+
+        // Already touching, so only the motion along the wall survives.
+        assert_close(&world.move_and_slide(&mover, &[1.0, 0.0, 1.0]), [0.0, 0.0, 1.0]);
+        assert_eq!(world.query_region(&AABB::new(1.5, 0.2, 4.2, 1.6, 0.3, 4.3)), vec![mover]);
+        assert_eq!(world.move_and_slide(&mover, &[-1.0, 0.0, 0.0]), vec![-1.0, 0.0, 0.0]);
+
+        let bodiless = world.spawn();
+        assert_eq!(world.move_and_slide(&bodiless, &[1.0, 1.0, 1.0]), vec![0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn move_and_slide_depenetrates_first() {
+        let mut world = World::new();
+        spawn_box(&mut world, AABB::new(2.0, -10.0, -10.0, 3.0, 10.0, 10.0));
+        let stuck = spawn_box(&mut world, AABB::new(2.5, 0.0, 0.0, 3.5, 1.0, 1.0));
+
+        assert_close(&world.move_and_slide(&stuck, &[0.0, 0.0, 0.0]), [0.5, 0.0, 0.0]);
+        assert_close(&world.move_and_slide(&stuck, &[-1.0, 0.0, 2.0]), [0.0, 0.0, 2.0]);
+    }
+
+    #[test]
+    fn move_and_slide_stops_when_wedged() {
+        // Depenetration pushes the box 0.2 out of the first wall and into the
+        // second, then 0.4 back out of that one, leaving it 0.2 to the left
+        // and overlapping the first wall again. The sweep then starts
+        // overlapping and reports no normal, so the box can't move along z.
+        let mut world = World::new();
+        spawn_box(&mut world, AABB::new(-1.0, -10.0, -10.0, 0.2, 10.0, 10.0));
+        spawn_box(&mut world, AABB::new(0.8, -10.0, -10.0, 2.0, 10.0, 10.0));
+        let wedged = spawn_box(&mut world, AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0));
+
+        let moved = world.move_and_slide(&wedged, &[0.0, 0.0, 1.0]);
+        assert_close(&moved, [-0.2, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn move_and_slide_into_a_corner_ends_within_the_slide_limit() {
+        let mut world = World::new();
+        spawn_box(&mut world, AABB::new(2.0, -10.0, -10.0, 3.0, 10.0, 10.0));
+        spawn_box(&mut world, AABB::new(-10.0, 2.0, -10.0, 10.0, 3.0, 10.0));
+        spawn_box(&mut world, AABB::new(-10.0, -10.0, 2.0, 10.0, 10.0, 3.0));
+        let mover = spawn_box(&mut world, AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0));
+
+        // Each contact removes one axis of motion, so three of the
+        // `MAX_SLIDES` passes bring it to rest in the corner.
+        assert_close(&world.move_and_slide(&mover, &[5.0, 5.0, 5.0]), [1.0, 1.0, 1.0]);
+        assert_close(&world.move_and_slide(&mover, &[1.0, 2.0, 3.0]), [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn despawned_handle_is_rejected_after_slot_reuse() {
+        let mut world = World::new();
+        let old = world.spawn();
+        world.insert_position(&old, 1.0, 2.0, 3.0);
+        assert!(world.despawn(&old));
+        assert!(!world.despawn(&old));
+
+        let new = world.spawn();
+        assert_eq!(new.index(), old.index());
+        assert_ne!(new.generation(), old.generation());
+        assert!(world.is_alive(&new) && !world.is_alive(&old));
+
+        assert_eq!(world.position(&new), None);
+        assert!(!world.insert_position(&old, 0.0, 0.0, 0.0));
+        assert!(world.insert(new, Speed(5.0)));
+        assert_eq!(world.get::<Speed>(old), None);
+        assert_eq!(world.remove::<Speed>(old), None);
+        assert!(!world.despawn(&old));
+        assert_eq!(world.get::<Speed>(new), Some(&Speed(5.0)));
+    }
+
+    #[test]
+    fn query_region_returns_the_covered_subset() {
+        let mut world = World::new();
+        let mut grid = Vec::new();
+        for x in 0..10 {
+            for z in 0..10 {
+                let (fx, fz) = (x as f32 * 5.0, z as f32 * 5.0);
+                grid.push((x, z, spawn_box(&mut world, AABB::new(fx, 0.0, fz, fx + 1.0, 1.0, fz + 1.0))));
+            }
+        }
+
+        let region = AABB::new(9.5, 0.0, 9.5, 20.5, 1.0, 15.5);
+        let want: Vec<Entity> = grid
+            .iter()
+            .filter(|(x, z, _)| (2..=4).contains(x) && (2..=3).contains(z))
+            .map(|&(_, _, entity)| entity)
+            .collect();
+        let mut got = world.query_region(&region);
+        got.sort_by_key(Entity::index);
+        assert_eq!(got, want);
+
+        world.despawn(&want[0]);
+        assert_eq!(world.query_region(&region).len(), 5);
+
+        // Moving a box re-indexes it; removing the box drops it.
+        let moved = grid[0].2;
+        world.insert_aabb(&moved, &AABB::new(100.0, 0.0, 0.0, 101.0, 1.0, 1.0));
+        assert_eq!(world.query_region(&AABB::new(99.0, 0.0, 0.0, 100.0, 1.0, 1.0)), vec![moved]);
+        assert!(world.query_region(&AABB::new(-1.0, 0.0, -1.0, 0.5, 1.0, 0.5)).is_empty());
+        world.remove::<AABB>(moved);
+        assert!(world.query_region(&AABB::new(99.0, 0.0, 0.0, 100.0, 1.0, 1.0)).is_empty());
+    }
+
+    #[test]
+    fn raycast_all_orders_hits_by_entry_distance() {
+        let mut world = World::new();
+        let far = spawn_box(&mut world, AABB::new(20.0, -1.0, -1.0, 22.0, 1.0, 1.0));
+        let near = spawn_box(&mut world, AABB::new(2.0, -1.0, -1.0, 3.0, 1.0, 1.0));
+        let mid = spawn_box(&mut world, AABB::new(9.0, -1.0, -1.0, 10.0, 1.0, 1.0));
+        spawn_box(&mut world, AABB::new(5.0, 5.0, 5.0, 6.0, 6.0, 6.0));
+        let around = spawn_box(&mut world, AABB::new(-1.0, -1.0, -1.0, 1.0, 1.0, 1.0));
+
+        let ray = Ray::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        assert_eq!(world.raycast_all(&ray, 100.0), vec![around, near, mid, far]);
+        assert_eq!(world.raycast_all(&ray, f32::INFINITY), vec![around, near, mid, far]);
+        assert_eq!(world.raycast_all(&ray, 9.0), vec![around, near, mid]);
+        assert_eq!(world.raycast_all(&ray, 8.0), vec![around, near]);
+        assert_eq!(world.raycast(&ray), Some(around));
+
+        world.despawn(&near);
+        assert_eq!(world.raycast_all(&ray, 100.0), vec![around, mid, far]);
+        assert!(world.raycast_all(&ray, -1.0).is_empty());
+        assert!(world.raycast_all(&ray, f32::NAN).is_empty());
+    }
+
+    #[test]
+    fn mutating_one_entity_marks_only_it() {
+        let mut world = World::new();
+        let a = world.spawn();
+        let b = world.spawn();
+        let c = spawn_box(&mut world, AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0));
+        world.insert_position(&a, 0.0, 0.0, 0.0);
+        world.insert_position(&b, 1.0, 0.0, 0.0);
+
+        // Everything inserted during tick 1 is newer than tick 0.
+        assert_eq!(world.current_tick(), 1);
+        assert_eq!(world.changed_since(0), vec![a, b, c]);
+
+        // `tick` returns the tick that ended; nothing has changed after it yet.
+        let last = world.tick();
+        assert_eq!((last, world.current_tick()), (1, 2));
+        assert!(world.changed_since(last).is_empty());
+        world.get_mut::<Position>(b).unwrap().0.x = 5.0;
+        assert_eq!(world.changed_since(last), vec![b]);
+
+        // Moving to another archetype keeps the other components' ticks.
+        // Removing a component isn't a change, and takes its tick with it.
+        let last = world.tick();
+        world.insert_aabb(&a, &AABB::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0));
+        assert_eq!(world.changed_since(last), vec![a]);
+        let last = world.tick();
+        world.remove::<AABB>(a);
+        assert!(world.changed_since(last).is_empty());
+        assert_eq!(world.changed_since(last - 2), vec![b]);
+
+        // Every `A` yielded by `iter_pair_mut` is marked, read or not.
+        let last = world.tick();
+        world.insert(c, Position(math::point(0.0, 0.0, 0.0)));
+        for _ in world.iter_pair_mut::<AABB, Position>() {}
+        let next = world.tick();
+        assert_eq!(world.changed_since(last), vec![c]);
+        assert!(world.changed_since(next).is_empty());
+        world.despawn(&c);
+        assert!(world.changed_since(last).is_empty());
+    }
+
+    #[test]
+    fn integrate_moves_positions_and_reindexes_boxes() {
+        let mut world = World::new();
+        let a = spawn_box(&mut world, AABB::new(-0.5, -0.5, -0.5, 0.5, 0.5, 0.5));
+        world.insert_position(&a, 0.0, 0.0, 0.0);
+        world.insert_velocity(&a, 10.0, 0.0, 0.0);
+        let b = world.spawn();
+        world.insert_position(&b, 0.0, 0.0, 0.0);
+        world.insert_velocity(&b, 0.0, -2.0, 0.0);
+        // No velocity, so it stays put.
+        let c = spawn_box(&mut world, AABB::new(0.5, 0.5, 0.5, 1.5, 1.5, 1.5));
+        world.insert_position(&c, 1.0, 1.0, 1.0);
+
+        let probe = AABB::new(9.0, -1.0, -1.0, 11.0, 1.0, 1.0);
+        assert!(world.query_region(&probe).is_empty());
+        world.integrate(0.5);
+        world.integrate(0.5);
+
+        assert_eq!(world.position(&a), Some(vec![10.0, 0.0, 0.0]));
+        assert_eq!(world.position(&b), Some(vec![0.0, -2.0, 0.0]));
+        assert_eq!(world.position(&c), Some(vec![1.0, 1.0, 1.0]));
+        assert_eq!(world.velocity(&a), Some(vec![10.0, 0.0, 0.0]));
+        assert_eq!(world.aabb(&a), Some(AABB::new(9.5, -0.5, -0.5, 10.5, 0.5, 0.5)));
+        assert_eq!(world.query_region(&probe), vec![a]);
+        assert_eq!(world.query_region(&AABB::new(-2.0, -2.0, -2.0, 0.6, 0.6, 0.6)), vec![c]);
+    }
+}